@@ -11,4 +11,19 @@ pub mod role {
     pub const WHITELISTER_ROLE: Symbol = symbol_short!("WLISTER");
     pub const WHITELISTED_ROLE: Symbol = symbol_short!("WLISTED");
     pub const REDEMPTION_EXECUTOR_ROLE: Symbol = symbol_short!("REXECUTOR");
+    pub const SNAPSHOT_ROLE: Symbol = symbol_short!("SNAPSHOT");
+    pub const DISTRIBUTOR_ROLE: Symbol = symbol_short!("DISTRIB");
+    pub const DOC_MANAGER_ROLE: Symbol = symbol_short!("DOCMGR");
+
+    pub const ALL_ROLES: [Symbol; 9] = [
+        MINTER_ROLE,
+        PAUSER_ROLE,
+        BURNER_ROLE,
+        WHITELISTER_ROLE,
+        WHITELISTED_ROLE,
+        REDEMPTION_EXECUTOR_ROLE,
+        SNAPSHOT_ROLE,
+        DISTRIBUTOR_ROLE,
+        DOC_MANAGER_ROLE,
+    ];
 }
@@ -0,0 +1,496 @@
+use soroban_sdk::{
+    contract, contractclient, contractimpl, contracttype, symbol_short,
+    token::TokenClient as AssetClient, Address, Env, Symbol, Vec,
+};
+use stellar_access::ownable::{self as ownable, Ownable};
+use stellar_contract_utils::upgradeable::UpgradeableInternal;
+use stellar_macros::{default_impl, only_owner, Upgradeable};
+
+use contracts_utils::role::{DISTRIBUTOR_ROLE, WHITELISTED_ROLE};
+
+#[contractclient(name = "PermissionManagerClient")]
+pub trait PermissionManagerInterface {
+    fn has_role(account: &Address, role: &Symbol) -> Option<u32>;
+    fn has_scoped_role(account: &Address, role: &Symbol, scope: &Address) -> bool;
+}
+
+#[contractclient(name = "TokenClient")]
+pub trait TokenInterface {
+    fn balance_at(e: Env, account: Address, snapshot_id: u32) -> i128;
+    fn total_supply_at(e: Env, snapshot_id: u32) -> i128;
+    fn decimals(e: Env) -> u32;
+    fn mint(e: Env, account: Address, amount: i128, caller: Address);
+}
+
+#[derive(Upgradeable)]
+#[contract]
+pub struct Distribution;
+
+pub const PERMISSION_MANAGER_KEY: Symbol = symbol_short!("PERM");
+pub const DISTRIBUTION_COUNT_KEY: Symbol = symbol_short!("COUNT");
+
+pub const DISTRIBUTION_EVENT: Symbol = symbol_short!("DISTRIB");
+pub const DISTRIBUTION_CREATED_EVENT: Symbol = symbol_short!("CREATE");
+pub const DISTRIBUTION_PAID_EVENT: Symbol = symbol_short!("PAID");
+pub const DISTRIBUTION_RECLAIMED_EVENT: Symbol = symbol_short!("RECLAIM");
+pub const DISTRIBUTION_REINVESTED_EVENT: Symbol = symbol_short!("REINVEST");
+pub const REINVESTMENT_ELECTED_EVENT: Symbol = symbol_short!("ELECT");
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    AllowedToken(Address),
+    Distribution(u32),
+    Paid(u32, Address),
+    Reinvestment(Address, Address),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DistributionEntry {
+    pub distributor: Address,
+    pub token: Address,
+    pub snapshot_id: u32,
+    pub asset: Address,
+    pub amount: i128,
+    pub total_supply: i128,
+    pub paid: i128,
+    pub deadline: u32,
+    pub nav: Option<i128>,
+    pub reclaimed: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DistributionPayment(pub u32, pub Address, pub i128);
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DistributionReinvestment(pub u32, pub Address, pub i128, pub i128);
+
+const ONE_DAY_LEDGERS: u32 = 17_280;
+const SIXTY_DAY_LEDGERS: u32 = ONE_DAY_LEDGERS * 60;
+
+#[contractimpl]
+impl Distribution {
+    pub fn __constructor(e: &Env, owner: Address) {
+        ownable::set_owner(e, &owner);
+    }
+
+    fn permission_manager_client(e: &Env) -> PermissionManagerClient<'_> {
+        let permission_manager: Address = e
+            .storage()
+            .instance()
+            .get(&PERMISSION_MANAGER_KEY)
+            .expect("Permission manager not set");
+        PermissionManagerClient::new(e, &permission_manager)
+    }
+
+    fn assert_has_role(e: &Env, account: &Address, role: &Symbol) {
+        let client = Self::permission_manager_client(e);
+        assert!(client.has_role(account, role).is_some(), "Invalid role");
+    }
+
+    fn assert_has_scoped_role(e: &Env, account: &Address, role: &Symbol, scope: &Address) {
+        let client = Self::permission_manager_client(e);
+        assert!(client.has_scoped_role(account, role, scope), "Invalid role");
+    }
+
+    /// Set the permission manager (central role management authority).
+    ///
+    /// # Arguments
+    ///
+    /// * `permission_manager` - The address of the permission manager.
+    ///
+    /// # Errors
+    ///
+    /// The caller must be the owner.
+    ///
+    #[only_owner]
+    pub fn set_permission_manager(e: &Env, permission_manager: Address) {
+        e.storage()
+            .instance()
+            .set(&PERMISSION_MANAGER_KEY, &permission_manager);
+    }
+
+    /// Allow or disallow distributions against a token.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The address of the token contract.
+    /// * `allowed` - Whether distributions can be created against the token.
+    ///
+    /// # Errors
+    ///
+    /// The caller must be the owner.
+    ///
+    #[only_owner]
+    pub fn set_token_allowed(e: &Env, token: Address, allowed: bool) {
+        let key = DataKey::AllowedToken(token);
+        if allowed {
+            e.storage().persistent().set(&key, &true);
+            e.storage()
+                .persistent()
+                .extend_ttl(&key, SIXTY_DAY_LEDGERS, SIXTY_DAY_LEDGERS);
+        } else {
+            e.storage().persistent().remove(&key);
+        }
+    }
+
+    /// Check whether distributions can be created against a token.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The address of the token contract.
+    pub fn is_token_allowed(e: &Env, token: Address) -> bool {
+        e.storage().persistent().has(&DataKey::AllowedToken(token))
+    }
+
+    fn set_distribution(e: &Env, distribution_id: u32, distribution: &DistributionEntry) {
+        let key = DataKey::Distribution(distribution_id);
+        e.storage().persistent().set(&key, distribution);
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, SIXTY_DAY_LEDGERS, SIXTY_DAY_LEDGERS);
+    }
+
+    /// Create a distribution. The amount is transferred from the caller and shared pro rata between the holders of the token at the snapshot.
+    ///
+    /// # Arguments
+    ///
+    /// * `caller` - The address of the distributor funding the distribution.
+    /// * `token` - The address of the token contract.
+    /// * `snapshot_id` - The snapshot ID of the token (record date).
+    /// * `asset` - The address of the asset paid out (e.g. a stablecoin).
+    /// * `amount` - The amount of asset to distribute.
+    /// * `deadline` - The ledger after which unpaid amounts can be reclaimed.
+    /// * `nav` - The net asset value of one token (10^decimals units) in asset units. When it is set, the shares of the holders who elected reinvestment are paid in newly minted tokens, which requires this contract to have the MINTER_ROLE on the token.
+    ///
+    /// # Errors
+    ///
+    /// The caller must have the DISTRIBUTOR_ROLE.
+    /// The token must be allowed by the owner.
+    /// The amount must be greater than zero.
+    /// The deadline must be in the future.
+    /// The NAV must be greater than zero when it is set.
+    /// The total supply at the snapshot must be greater than zero.
+    ///
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_distribution(
+        e: &Env,
+        caller: Address,
+        token: Address,
+        snapshot_id: u32,
+        asset: Address,
+        amount: i128,
+        deadline: u32,
+        nav: Option<i128>,
+    ) -> u32 {
+        caller.require_auth();
+        Self::assert_has_role(e, &caller, &DISTRIBUTOR_ROLE);
+        assert!(
+            Self::is_token_allowed(e, token.clone()),
+            "Token not allowed"
+        );
+        assert!(amount > 0, "Invalid zero-amount distribution");
+        assert!(deadline > e.ledger().sequence(), "Invalid deadline");
+        assert!(nav.is_none_or(|nav| nav > 0), "Invalid NAV");
+
+        let total_supply = TokenClient::new(e, &token).total_supply_at(&snapshot_id);
+        assert!(total_supply > 0, "Empty snapshot");
+
+        AssetClient::new(e, &asset).transfer(&caller, &e.current_contract_address(), &amount);
+
+        let distribution_id: u32 = e
+            .storage()
+            .instance()
+            .get(&DISTRIBUTION_COUNT_KEY)
+            .unwrap_or(0);
+        e.storage()
+            .instance()
+            .set(&DISTRIBUTION_COUNT_KEY, &(distribution_id + 1));
+
+        let distribution = DistributionEntry {
+            distributor: caller,
+            token,
+            snapshot_id,
+            asset,
+            amount,
+            total_supply,
+            paid: 0,
+            deadline,
+            nav,
+            reclaimed: false,
+        };
+        Self::set_distribution(e, distribution_id, &distribution);
+
+        e.events().publish(
+            (DISTRIBUTION_EVENT, DISTRIBUTION_CREATED_EVENT),
+            (distribution_id, distribution),
+        );
+        distribution_id
+    }
+
+    /// Get a distribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `distribution_id` - The distribution ID.
+    pub fn get_distribution(e: &Env, distribution_id: u32) -> DistributionEntry {
+        e.storage()
+            .persistent()
+            .get(&DataKey::Distribution(distribution_id))
+            .expect("Distribution not found")
+    }
+
+    /// Get the share of a holder in a distribution, rounded down.
+    ///
+    /// # Arguments
+    ///
+    /// * `distribution_id` - The distribution ID.
+    /// * `holder` - The address of the holder.
+    pub fn share_of(e: &Env, distribution_id: u32, holder: Address) -> i128 {
+        let distribution = Self::get_distribution(e, distribution_id);
+        Self::compute_share(e, &distribution, &holder)
+    }
+
+    /// Check whether a holder has been paid its share of a distribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `distribution_id` - The distribution ID.
+    /// * `holder` - The address of the holder.
+    pub fn is_paid(e: &Env, distribution_id: u32, holder: Address) -> bool {
+        e.storage()
+            .persistent()
+            .has(&DataKey::Paid(distribution_id, holder))
+    }
+
+    fn compute_share(e: &Env, distribution: &DistributionEntry, holder: &Address) -> i128 {
+        let balance =
+            TokenClient::new(e, &distribution.token).balance_at(holder, &distribution.snapshot_id);
+        distribution.amount * balance / distribution.total_supply
+    }
+
+    /// Pay the share of a holder. Holders that have already been paid are skipped. The distribution never pays out more than its amount, whatever the balances reported by the token, so that it cannot draw on the funds of other distributions of the same asset.
+    fn pay(e: &Env, distribution_id: u32, distribution: &mut DistributionEntry, holder: &Address) {
+        if Self::is_paid(e, distribution_id, holder.clone()) {
+            return;
+        }
+        Self::assert_has_scoped_role(e, holder, &WHITELISTED_ROLE, &distribution.token);
+
+        let share = Self::compute_share(e, distribution, holder);
+        assert!(
+            share >= 0 && distribution.paid + share <= distribution.amount,
+            "Distribution exceeded"
+        );
+
+        let paid_key = DataKey::Paid(distribution_id, holder.clone());
+        e.storage().persistent().set(&paid_key, &true);
+        e.storage()
+            .persistent()
+            .extend_ttl(&paid_key, SIXTY_DAY_LEDGERS, SIXTY_DAY_LEDGERS);
+        distribution.paid += share;
+
+        if let Some(nav) = distribution.nav {
+            if Self::is_reinvesting(e, distribution.token.clone(), holder.clone()) {
+                Self::reinvest(e, distribution_id, distribution, holder, share, nav);
+                return;
+            }
+        }
+
+        if share > 0 {
+            AssetClient::new(e, &distribution.asset).transfer(
+                &e.current_contract_address(),
+                holder,
+                &share,
+            );
+        }
+        e.events().publish(
+            (DISTRIBUTION_EVENT, DISTRIBUTION_PAID_EVENT),
+            DistributionPayment(distribution_id, holder.clone(), share),
+        );
+    }
+
+    /// Pay the share of a holder in newly minted tokens at the NAV of the distribution. The asset is returned to the distributor, rounding remainder included.
+    fn reinvest(
+        e: &Env,
+        distribution_id: u32,
+        distribution: &DistributionEntry,
+        holder: &Address,
+        share: i128,
+        nav: i128,
+    ) {
+        let token_client = TokenClient::new(e, &distribution.token);
+        let minted = share * 10_i128.pow(token_client.decimals()) / nav;
+
+        if minted > 0 {
+            token_client.mint(holder, &minted, &e.current_contract_address());
+        }
+        if share > 0 {
+            AssetClient::new(e, &distribution.asset).transfer(
+                &e.current_contract_address(),
+                &distribution.distributor,
+                &share,
+            );
+        }
+        e.events().publish(
+            (DISTRIBUTION_EVENT, DISTRIBUTION_REINVESTED_EVENT),
+            DistributionReinvestment(distribution_id, holder.clone(), share, minted),
+        );
+    }
+
+    /// Elect or revoke the reinvestment of the distributions of a token. Reinvesting holders receive newly minted tokens instead of the asset when a distribution is declared with a NAV.
+    ///
+    /// # Arguments
+    ///
+    /// * `holder` - The address of the holder.
+    /// * `token` - The address of the token contract.
+    /// * `elected` - Whether the holder reinvests the distributions.
+    ///
+    pub fn set_reinvestment(e: &Env, holder: Address, token: Address, elected: bool) {
+        holder.require_auth();
+
+        let key = DataKey::Reinvestment(token.clone(), holder.clone());
+        if elected {
+            e.storage().persistent().set(&key, &true);
+            e.storage()
+                .persistent()
+                .extend_ttl(&key, SIXTY_DAY_LEDGERS, SIXTY_DAY_LEDGERS);
+        } else {
+            e.storage().persistent().remove(&key);
+        }
+
+        e.events().publish(
+            (DISTRIBUTION_EVENT, REINVESTMENT_ELECTED_EVENT),
+            (token, holder, elected),
+        );
+    }
+
+    /// Check whether a holder reinvests the distributions of a token.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The address of the token contract.
+    /// * `holder` - The address of the holder.
+    pub fn is_reinvesting(e: &Env, token: Address, holder: Address) -> bool {
+        e.storage()
+            .persistent()
+            .has(&DataKey::Reinvestment(token, holder))
+    }
+
+    fn open_distribution(e: &Env, distribution_id: u32) -> DistributionEntry {
+        let distribution = Self::get_distribution(e, distribution_id);
+        assert!(
+            e.ledger().sequence() <= distribution.deadline,
+            "Distribution expired"
+        );
+        distribution
+    }
+
+    /// Claim the share of a holder in a distribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `holder` - The address of the holder.
+    /// * `distribution_id` - The distribution ID.
+    ///
+    /// # Errors
+    ///
+    /// The holder must have the WHITELISTED_ROLE, globally or for the token.
+    /// The holder must not have been paid already.
+    /// The deadline of the distribution must not be passed.
+    ///
+    pub fn claim(e: &Env, holder: Address, distribution_id: u32) {
+        holder.require_auth();
+        assert!(
+            !Self::is_paid(e, distribution_id, holder.clone()),
+            "Already paid"
+        );
+        let mut distribution = Self::open_distribution(e, distribution_id);
+        Self::pay(e, distribution_id, &mut distribution, &holder);
+        Self::set_distribution(e, distribution_id, &distribution);
+    }
+
+    /// Push the shares of a batch of holders in a distribution. Holders that have already been paid are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `caller` - The address of the distributor.
+    /// * `distribution_id` - The distribution ID.
+    /// * `holders` - The addresses of the holders.
+    ///
+    /// # Errors
+    ///
+    /// The caller must have the DISTRIBUTOR_ROLE.
+    /// The batch must not be empty.
+    /// All holders must have the WHITELISTED_ROLE, globally or for the token.
+    /// The deadline of the distribution must not be passed.
+    ///
+    pub fn push(e: &Env, caller: Address, distribution_id: u32, holders: Vec<Address>) {
+        caller.require_auth();
+        Self::assert_has_role(e, &caller, &DISTRIBUTOR_ROLE);
+        assert!(!holders.is_empty(), "Empty batch");
+
+        let mut distribution = Self::open_distribution(e, distribution_id);
+        for holder in &holders {
+            Self::pay(e, distribution_id, &mut distribution, &holder);
+        }
+        Self::set_distribution(e, distribution_id, &distribution);
+    }
+
+    /// Reclaim the unpaid amount of a distribution once its deadline is passed. It is returned to the distributor who funded the distribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `caller` - The address of a distributor.
+    /// * `distribution_id` - The distribution ID.
+    ///
+    /// # Errors
+    ///
+    /// The caller must have the DISTRIBUTOR_ROLE.
+    /// The deadline of the distribution must be passed.
+    /// The distribution must not have been reclaimed already.
+    ///
+    pub fn reclaim(e: &Env, caller: Address, distribution_id: u32) {
+        caller.require_auth();
+        Self::assert_has_role(e, &caller, &DISTRIBUTOR_ROLE);
+
+        let mut distribution = Self::get_distribution(e, distribution_id);
+        assert!(
+            e.ledger().sequence() > distribution.deadline,
+            "Distribution not expired"
+        );
+        assert!(!distribution.reclaimed, "Already reclaimed");
+
+        let remaining = distribution.amount - distribution.paid;
+        distribution.reclaimed = true;
+        Self::set_distribution(e, distribution_id, &distribution);
+
+        if remaining > 0 {
+            AssetClient::new(e, &distribution.asset).transfer(
+                &e.current_contract_address(),
+                &distribution.distributor,
+                &remaining,
+            );
+        }
+        e.events().publish(
+            (DISTRIBUTION_EVENT, DISTRIBUTION_RECLAIMED_EVENT),
+            DistributionPayment(distribution_id, distribution.distributor, remaining),
+        );
+    }
+}
+
+#[default_impl]
+#[contractimpl]
+impl Ownable for Distribution {}
+
+impl UpgradeableInternal for Distribution {
+    fn _require_auth(e: &Env, operator: &Address) {
+        operator.require_auth();
+        let owner = ownable::get_owner(e).expect("Owner not set");
+        if *operator != owner {
+            panic!("Only owner can call this function");
+        }
+    }
+}
@@ -0,0 +1,657 @@
+#![cfg(test)]
+
+extern crate std;
+
+use super::contract::{Distribution, DistributionArgs, DistributionClient};
+use contracts_utils::role::{DISTRIBUTOR_ROLE, MINTER_ROLE, SNAPSHOT_ROLE, WHITELISTED_ROLE};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient as AssetClient},
+    Address, Env, String, Vec,
+};
+
+mod permission_manager {
+    use soroban_sdk::contractimport;
+
+    contractimport!(file = "../../wasm/permission_manager.wasm");
+}
+
+mod token_contract {
+    use soroban_sdk::{contractclient, Address, Env};
+
+    pub const WASM: &[u8] = include_bytes!("../../../wasm/token.wasm");
+
+    #[contractclient(name = "Client")]
+    pub trait TokenInterface {
+        fn set_permission_manager(e: Env, permission_manager: Address);
+        fn mint(e: Env, account: Address, amount: i128, caller: Address);
+        fn transfer(e: Env, from: Address, to: Address, amount: i128);
+        fn snapshot(e: Env, caller: Address) -> u32;
+    }
+}
+
+mod token {
+    use soroban_sdk::{contract, contractimpl, contracttype, Address, Env};
+
+    use crate::contract::TokenInterface;
+
+    #[contracttype]
+    enum MockKey {
+        BalanceAt(Address, u32),
+        TotalSupplyAt(u32),
+        Balance(Address),
+    }
+
+    #[contract]
+    pub struct Mock;
+
+    #[contractimpl]
+    impl Mock {
+        pub fn balance(e: Env, account: Address) -> i128 {
+            e.storage()
+                .instance()
+                .get(&MockKey::Balance(account))
+                .unwrap_or(0)
+        }
+
+        pub fn set_balance_at(e: Env, account: Address, snapshot_id: u32, balance: i128) {
+            let total_supply = Self::total_supply_at(e.clone(), snapshot_id)
+                - Self::balance_at(e.clone(), account.clone(), snapshot_id)
+                + balance;
+            e.storage()
+                .instance()
+                .set(&MockKey::BalanceAt(account, snapshot_id), &balance);
+            e.storage()
+                .instance()
+                .set(&MockKey::TotalSupplyAt(snapshot_id), &total_supply);
+        }
+    }
+
+    #[contractimpl]
+    impl TokenInterface for Mock {
+        fn balance_at(e: Env, account: Address, snapshot_id: u32) -> i128 {
+            e.storage()
+                .instance()
+                .get(&MockKey::BalanceAt(account, snapshot_id))
+                .unwrap_or(0)
+        }
+
+        fn total_supply_at(e: Env, snapshot_id: u32) -> i128 {
+            e.storage()
+                .instance()
+                .get(&MockKey::TotalSupplyAt(snapshot_id))
+                .unwrap_or(0)
+        }
+
+        fn decimals(_e: Env) -> u32 {
+            5
+        }
+
+        fn mint(e: Env, account: Address, amount: i128, caller: Address) {
+            caller.require_auth();
+            let balance = Mock::balance(e.clone(), account.clone()) + amount;
+            e.storage()
+                .instance()
+                .set(&MockKey::Balance(account), &balance);
+        }
+    }
+}
+
+struct Setup<'a> {
+    admin: Address,
+    distributor: Address,
+    token: token::MockClient<'a>,
+    asset: AssetClient<'a>,
+    permission_manager: permission_manager::Client<'a>,
+    client: DistributionClient<'a>,
+}
+
+fn setup_env() -> Env {
+    let e: Env = Env::default();
+    e.mock_all_auths();
+    e
+}
+
+fn deploy_distribution(e: &Env) -> (Address, Address, DistributionClient<'_>) {
+    let owner: Address = Address::generate(e);
+    let distribution_address = e.register(
+        Distribution,
+        DistributionArgs::__constructor(&owner.clone()),
+    );
+    let client = DistributionClient::new(e, &distribution_address);
+
+    (owner, distribution_address, client)
+}
+
+fn setup(e: &Env) -> Setup<'_> {
+    let admin: Address = Address::generate(e);
+    let distributor: Address = Address::generate(e);
+
+    let permission_manager_address = e.register(
+        permission_manager::WASM,
+        permission_manager::Args::__constructor(&admin.clone()),
+    );
+    let permission_manager = permission_manager::Client::new(e, &permission_manager_address);
+
+    let token_address = e.register(token::Mock, ());
+    let token = token::MockClient::new(e, &token_address);
+
+    let asset_address = e
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    let asset = AssetClient::new(e, &asset_address);
+    StellarAssetClient::new(e, &asset_address).mint(&distributor, &1_000_000);
+
+    let (_, _, client) = deploy_distribution(e);
+    client.set_permission_manager(&permission_manager_address);
+    client.set_token_allowed(&token_address, &true);
+
+    permission_manager.grant_role(&admin, &distributor, &DISTRIBUTOR_ROLE);
+
+    Setup {
+        admin,
+        distributor,
+        token,
+        asset,
+        permission_manager,
+        client,
+    }
+}
+
+fn take_snapshot(setup: &Setup, holders: &[(&Address, i128)]) -> u32 {
+    let snapshot_id: u32 = 1;
+    for (holder, balance) in holders {
+        setup
+            .permission_manager
+            .grant_role(&setup.admin, holder, &WHITELISTED_ROLE);
+        setup.token.set_balance_at(holder, &snapshot_id, balance);
+    }
+    snapshot_id
+}
+
+#[test]
+fn test_should_set_owner_on_constructor() {
+    let e = setup_env();
+    let (owner, _, client) = deploy_distribution(&e);
+
+    let fetched_owner = client.get_owner();
+
+    assert_eq!(fetched_owner, Some(owner));
+}
+
+#[test]
+fn test_create_distribution_should_fund_the_distribution() {
+    let e = setup_env();
+    let setup = setup(&e);
+    let holder: Address = Address::generate(&e);
+    let snapshot_id = take_snapshot(&setup, &[(&holder, 100)]);
+
+    let distribution_id = setup.client.create_distribution(
+        &setup.distributor,
+        &setup.token.address,
+        &snapshot_id,
+        &setup.asset.address,
+        &1_000,
+        &100,
+        &None,
+    );
+
+    let distribution = setup.client.get_distribution(&distribution_id);
+    assert_eq!(distribution.amount, 1_000);
+    assert_eq!(distribution.total_supply, 100);
+    assert_eq!(setup.asset.balance(&setup.client.address), 1_000);
+    assert_eq!(setup.asset.balance(&setup.distributor), 999_000);
+}
+
+#[test]
+fn test_create_distribution_should_fail_if_token_is_not_allowed() {
+    let e = setup_env();
+    let setup = setup(&e);
+    let holder: Address = Address::generate(&e);
+    let snapshot_id = take_snapshot(&setup, &[(&holder, 100)]);
+    setup.client.set_token_allowed(&setup.token.address, &false);
+
+    let result = setup.client.try_create_distribution(
+        &setup.distributor,
+        &setup.token.address,
+        &snapshot_id,
+        &setup.asset.address,
+        &1_000,
+        &100,
+        &None,
+    );
+
+    assert!(result.is_err());
+    assert!(!setup.client.is_token_allowed(&setup.token.address));
+}
+
+#[test]
+fn test_create_distribution_should_fail_if_not_distributor() {
+    let e = setup_env();
+    let setup = setup(&e);
+    let holder: Address = Address::generate(&e);
+    let snapshot_id = take_snapshot(&setup, &[(&holder, 100)]);
+
+    let result = setup.client.try_create_distribution(
+        &holder,
+        &setup.token.address,
+        &snapshot_id,
+        &setup.asset.address,
+        &1_000,
+        &100,
+        &None,
+    );
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_claim_should_pay_pro_rata_share_at_snapshot() {
+    let e = setup_env();
+    let setup = setup(&e);
+    let holder1: Address = Address::generate(&e);
+    let holder2: Address = Address::generate(&e);
+    let snapshot_id = take_snapshot(&setup, &[(&holder1, 100), (&holder2, 200)]);
+    setup.token.set_balance_at(&holder1, &2, &1_000);
+    let distribution_id = setup.client.create_distribution(
+        &setup.distributor,
+        &setup.token.address,
+        &snapshot_id,
+        &setup.asset.address,
+        &1_000,
+        &100,
+        &None,
+    );
+
+    setup.client.claim(&holder1, &distribution_id);
+    setup.client.claim(&holder2, &distribution_id);
+
+    assert_eq!(setup.asset.balance(&holder1), 333);
+    assert_eq!(setup.asset.balance(&holder2), 666);
+    assert!(setup.client.is_paid(&distribution_id, &holder1));
+    assert_eq!(setup.client.get_distribution(&distribution_id).paid, 999);
+}
+
+#[test]
+fn test_claim_should_fail_if_already_paid() {
+    let e = setup_env();
+    let setup = setup(&e);
+    let holder: Address = Address::generate(&e);
+    let snapshot_id = take_snapshot(&setup, &[(&holder, 100)]);
+    let distribution_id = setup.client.create_distribution(
+        &setup.distributor,
+        &setup.token.address,
+        &snapshot_id,
+        &setup.asset.address,
+        &1_000,
+        &100,
+        &None,
+    );
+    setup.client.claim(&holder, &distribution_id);
+
+    let result = setup.client.try_claim(&holder, &distribution_id);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_claim_should_fail_if_holder_is_not_whitelisted() {
+    let e = setup_env();
+    let setup = setup(&e);
+    let holder: Address = Address::generate(&e);
+    let snapshot_id = take_snapshot(&setup, &[(&holder, 100)]);
+    let distribution_id = setup.client.create_distribution(
+        &setup.distributor,
+        &setup.token.address,
+        &snapshot_id,
+        &setup.asset.address,
+        &1_000,
+        &100,
+        &None,
+    );
+    setup
+        .permission_manager
+        .revoke_role(&setup.admin, &holder, &WHITELISTED_ROLE);
+
+    let result = setup.client.try_claim(&holder, &distribution_id);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_claim_should_fail_after_deadline() {
+    let e = setup_env();
+    let setup = setup(&e);
+    let holder: Address = Address::generate(&e);
+    let snapshot_id = take_snapshot(&setup, &[(&holder, 100)]);
+    let distribution_id = setup.client.create_distribution(
+        &setup.distributor,
+        &setup.token.address,
+        &snapshot_id,
+        &setup.asset.address,
+        &1_000,
+        &100,
+        &None,
+    );
+    e.ledger().set_sequence_number(101);
+
+    let result = setup.client.try_claim(&holder, &distribution_id);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_push_should_pay_holders_and_skip_paid_ones() {
+    let e = setup_env();
+    let setup = setup(&e);
+    let holder1: Address = Address::generate(&e);
+    let holder2: Address = Address::generate(&e);
+    let snapshot_id = take_snapshot(&setup, &[(&holder1, 100), (&holder2, 300)]);
+    let distribution_id = setup.client.create_distribution(
+        &setup.distributor,
+        &setup.token.address,
+        &snapshot_id,
+        &setup.asset.address,
+        &1_000,
+        &100,
+        &None,
+    );
+    setup.client.claim(&holder1, &distribution_id);
+    let mut holders = Vec::new(&e);
+    holders.push_back(holder1.clone());
+    holders.push_back(holder2.clone());
+
+    setup
+        .client
+        .push(&setup.distributor, &distribution_id, &holders);
+
+    assert_eq!(setup.asset.balance(&holder1), 250);
+    assert_eq!(setup.asset.balance(&holder2), 750);
+}
+
+#[test]
+fn test_push_should_not_pay_more_than_the_distribution_amount() {
+    let e = setup_env();
+    let setup = setup(&e);
+    let holder1: Address = Address::generate(&e);
+    let holder2: Address = Address::generate(&e);
+    let snapshot_id = take_snapshot(&setup, &[(&holder1, 100), (&holder2, 100)]);
+    let first_distribution_id = setup.client.create_distribution(
+        &setup.distributor,
+        &setup.token.address,
+        &snapshot_id,
+        &setup.asset.address,
+        &1_000,
+        &100,
+        &None,
+    );
+    let second_distribution_id = setup.client.create_distribution(
+        &setup.distributor,
+        &setup.token.address,
+        &snapshot_id,
+        &setup.asset.address,
+        &1_000,
+        &100,
+        &None,
+    );
+    setup.token.set_balance_at(&holder1, &snapshot_id, &150);
+    let mut holders = Vec::new(&e);
+    holders.push_back(holder1.clone());
+    holders.push_back(holder2.clone());
+
+    let result = setup
+        .client
+        .try_push(&setup.distributor, &first_distribution_id, &holders);
+
+    assert!(result.is_err());
+    assert_eq!(setup.asset.balance(&setup.client.address), 2_000);
+    setup.client.claim(&holder1, &first_distribution_id);
+    assert!(setup
+        .client
+        .try_claim(&holder2, &first_distribution_id)
+        .is_err());
+    assert_eq!(setup.asset.balance(&holder1), 750);
+    assert_eq!(
+        setup.client.get_distribution(&first_distribution_id).paid,
+        750
+    );
+    assert_eq!(
+        setup.client.get_distribution(&second_distribution_id).paid,
+        0
+    );
+}
+
+#[test]
+fn test_push_should_fail_if_batch_is_empty() {
+    let e = setup_env();
+    let setup = setup(&e);
+    let holder: Address = Address::generate(&e);
+    let snapshot_id = take_snapshot(&setup, &[(&holder, 100)]);
+    let distribution_id = setup.client.create_distribution(
+        &setup.distributor,
+        &setup.token.address,
+        &snapshot_id,
+        &setup.asset.address,
+        &1_000,
+        &100,
+        &None,
+    );
+
+    let result = setup
+        .client
+        .try_push(&setup.distributor, &distribution_id, &Vec::new(&e));
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_reclaim_should_transfer_unpaid_amount_after_deadline() {
+    let e = setup_env();
+    let setup = setup(&e);
+    let holder1: Address = Address::generate(&e);
+    let holder2: Address = Address::generate(&e);
+    let snapshot_id = take_snapshot(&setup, &[(&holder1, 100), (&holder2, 300)]);
+    let distribution_id = setup.client.create_distribution(
+        &setup.distributor,
+        &setup.token.address,
+        &snapshot_id,
+        &setup.asset.address,
+        &1_000,
+        &100,
+        &None,
+    );
+    setup.client.claim(&holder1, &distribution_id);
+    e.ledger().set_sequence_number(101);
+
+    setup.client.reclaim(&setup.distributor, &distribution_id);
+
+    assert_eq!(setup.asset.balance(&setup.distributor), 999_750);
+    assert_eq!(setup.asset.balance(&setup.client.address), 0);
+    assert!(setup
+        .client
+        .try_reclaim(&setup.distributor, &distribution_id)
+        .is_err());
+}
+
+#[test]
+fn test_reclaim_should_return_unpaid_amount_to_the_funding_distributor() {
+    let e = setup_env();
+    let setup = setup(&e);
+    let distributor2: Address = Address::generate(&e);
+    setup
+        .permission_manager
+        .grant_role(&setup.admin, &distributor2, &DISTRIBUTOR_ROLE);
+    let holder: Address = Address::generate(&e);
+    let snapshot_id = take_snapshot(&setup, &[(&holder, 100)]);
+    let distribution_id = setup.client.create_distribution(
+        &setup.distributor,
+        &setup.token.address,
+        &snapshot_id,
+        &setup.asset.address,
+        &1_000,
+        &100,
+        &None,
+    );
+    e.ledger().set_sequence_number(101);
+
+    setup.client.reclaim(&distributor2, &distribution_id);
+
+    assert_eq!(setup.asset.balance(&setup.distributor), 1_000_000);
+    assert_eq!(setup.asset.balance(&distributor2), 0);
+    assert_eq!(setup.asset.balance(&setup.client.address), 0);
+}
+
+#[test]
+fn test_reclaim_should_fail_before_deadline() {
+    let e = setup_env();
+    let setup = setup(&e);
+    let holder: Address = Address::generate(&e);
+    let snapshot_id = take_snapshot(&setup, &[(&holder, 100)]);
+    let distribution_id = setup.client.create_distribution(
+        &setup.distributor,
+        &setup.token.address,
+        &snapshot_id,
+        &setup.asset.address,
+        &1_000,
+        &100,
+        &None,
+    );
+
+    let result = setup
+        .client
+        .try_reclaim(&setup.distributor, &distribution_id);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_set_reinvestment_should_require_holder_auth() {
+    let e = setup_env();
+    let (_, _, client) = deploy_distribution(&e);
+    let holder: Address = Address::generate(&e);
+    let token: Address = Address::generate(&e);
+
+    client.set_reinvestment(&holder, &token, &true);
+
+    let auths = e.auths();
+    assert_eq!(auths.len(), 1);
+    let (addr, _invocation) = &auths[0];
+    assert_eq!(addr, &holder);
+    assert!(client.is_reinvesting(&token, &holder));
+
+    client.set_reinvestment(&holder, &token, &false);
+
+    assert!(!client.is_reinvesting(&token, &holder));
+}
+
+#[test]
+fn test_push_should_mint_tokens_to_reinvesting_holders_when_nav_is_set() {
+    let e = setup_env();
+    let setup = setup(&e);
+    let holder1: Address = Address::generate(&e);
+    let holder2: Address = Address::generate(&e);
+    let snapshot_id = take_snapshot(&setup, &[(&holder1, 100), (&holder2, 300)]);
+    setup
+        .client
+        .set_reinvestment(&holder1, &setup.token.address, &true);
+    let distribution_id = setup.client.create_distribution(
+        &setup.distributor,
+        &setup.token.address,
+        &snapshot_id,
+        &setup.asset.address,
+        &1_000,
+        &100,
+        &Some(200_000),
+    );
+    let mut holders = Vec::new(&e);
+    holders.push_back(holder1.clone());
+    holders.push_back(holder2.clone());
+
+    setup
+        .client
+        .push(&setup.distributor, &distribution_id, &holders);
+
+    assert_eq!(setup.token.balance(&holder1), 125);
+    assert_eq!(setup.asset.balance(&holder1), 0);
+    assert_eq!(setup.token.balance(&holder2), 0);
+    assert_eq!(setup.asset.balance(&holder2), 750);
+    assert_eq!(setup.asset.balance(&setup.distributor), 999_250);
+}
+
+#[test]
+fn test_claim_should_pay_asset_to_reinvesting_holders_when_nav_is_not_set() {
+    let e = setup_env();
+    let setup = setup(&e);
+    let holder: Address = Address::generate(&e);
+    let snapshot_id = take_snapshot(&setup, &[(&holder, 100)]);
+    setup
+        .client
+        .set_reinvestment(&holder, &setup.token.address, &true);
+    let distribution_id = setup.client.create_distribution(
+        &setup.distributor,
+        &setup.token.address,
+        &snapshot_id,
+        &setup.asset.address,
+        &1_000,
+        &100,
+        &None,
+    );
+
+    setup.client.claim(&holder, &distribution_id);
+
+    assert_eq!(setup.token.balance(&holder), 0);
+    assert_eq!(setup.asset.balance(&holder), 1_000);
+}
+
+#[test]
+fn test_claim_should_pay_pro_rata_share_at_a_token_snapshot() {
+    let e = setup_env();
+    let setup = setup(&e);
+    let token_owner: Address = Address::generate(&e);
+    let minter: Address = Address::generate(&e);
+    let snapshotter: Address = Address::generate(&e);
+    let holder1: Address = Address::generate(&e);
+    let holder2: Address = Address::generate(&e);
+    let token_address = e.register(
+        token_contract::WASM,
+        (
+            token_owner,
+            String::from_str(&e, "Token"),
+            String::from_str(&e, "EUTBL"),
+            5_u32,
+        ),
+    );
+    let token = token_contract::Client::new(&e, &token_address);
+    token.set_permission_manager(&setup.permission_manager.address);
+    setup.client.set_token_allowed(&token_address, &true);
+    for (account, role) in [
+        (&minter, MINTER_ROLE),
+        (&snapshotter, SNAPSHOT_ROLE),
+        (&holder1, WHITELISTED_ROLE),
+        (&holder2, WHITELISTED_ROLE),
+    ] {
+        setup
+            .permission_manager
+            .grant_role(&setup.admin, account, &role);
+    }
+    token.mint(&holder1, &100, &minter);
+    token.mint(&holder2, &300, &minter);
+    let snapshot_id = token.snapshot(&snapshotter);
+    token.transfer(&holder2, &holder1, &300);
+    let distribution_id = setup.client.create_distribution(
+        &setup.distributor,
+        &token_address,
+        &snapshot_id,
+        &setup.asset.address,
+        &1_000,
+        &100,
+        &None,
+    );
+
+    setup.client.claim(&holder1, &distribution_id);
+    setup.client.claim(&holder2, &distribution_id);
+
+    assert_eq!(setup.asset.balance(&holder1), 250);
+    assert_eq!(setup.asset.balance(&holder2), 750);
+    assert_eq!(setup.client.get_distribution(&distribution_id).paid, 1_000);
+}
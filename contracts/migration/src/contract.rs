@@ -0,0 +1,330 @@
+use soroban_sdk::{
+    contract, contractclient, contractimpl, contracttype, symbol_short, Address, Env, Symbol, Vec,
+};
+use stellar_access::ownable::{self as ownable, Ownable};
+use stellar_macros::{default_impl, only_owner};
+
+use contracts_utils::role::WHITELISTED_ROLE;
+
+#[contractclient(name = "PermissionManagerClient")]
+pub trait PermissionManagerInterface {
+    fn has_scoped_roles(accounts: &Vec<Address>, role: &Symbol, scope: &Address) -> Vec<bool>;
+}
+
+#[contractclient(name = "TokenClient")]
+pub trait TokenInterface {
+    fn total_supply(e: Env) -> i128;
+    fn holder_count(e: Env) -> u32;
+    fn holders(e: Env, cursor: u32, limit: u32) -> Vec<(Address, i128)>;
+    fn burn(e: Env, account: Address, amount: i128, caller: Address);
+    fn mint(e: Env, account: Address, amount: i128, caller: Address);
+}
+
+#[contract]
+pub struct Migration;
+
+pub const FROM_TOKEN_KEY: Symbol = symbol_short!("FROM");
+pub const TO_TOKEN_KEY: Symbol = symbol_short!("TO");
+pub const RATIO_KEY: Symbol = symbol_short!("RATIO");
+pub const PROGRESS_KEY: Symbol = symbol_short!("PROGRESS");
+pub const PERMISSION_MANAGER_KEY: Symbol = symbol_short!("PERM");
+pub const HOLDER_CHECK_KEY: Symbol = symbol_short!("CHECK");
+pub const SKIP_ROUND_KEY: Symbol = symbol_short!("SKIPROUND");
+
+pub const MIGRATION_EVENT: Symbol = symbol_short!("MIGRATE");
+pub const HOLDER_MIGRATED_EVENT: Symbol = symbol_short!("HOLDER");
+pub const HOLDER_SKIPPED_EVENT: Symbol = symbol_short!("SKIPPED");
+pub const BATCH_MIGRATED_EVENT: Symbol = symbol_short!("BATCH");
+
+// Each holder costs a burn and a mint on two other contracts.
+const MAX_BATCH_SIZE: u32 = 20;
+const HOLDERS_PAGE_SIZE: u32 = 100;
+const ONE_DAY_LEDGERS: u32 = 17_280;
+const SIXTY_DAY_LEDGERS: u32 = ONE_DAY_LEDGERS * 60;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    /// The round of `retry_skipped_holders` in which a holder was skipped.
+    Skipped(Address),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MigrationProgress {
+    pub batches: u32,
+    pub migrated_holders: u32,
+    pub burned_amount: i128,
+    pub minted_amount: i128,
+    pub skipped_holders: u32,
+}
+
+/// Progress of the check that the holder set of the from token covers its whole supply.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct HolderCheck {
+    pub checked_holders: u32,
+    pub checked_amount: i128,
+    pub completed: bool,
+}
+
+#[contractimpl]
+impl Migration {
+    /// The migration contract converts every holder of the from token into the to token at a fixed ratio. It must have the BURNER_ROLE and the MINTER_ROLE. Holders without the WHITELISTED_ROLE on the to token are skipped.
+    pub fn __constructor(
+        e: &Env,
+        owner: Address,
+        from_token: Address,
+        to_token: Address,
+        numerator: i128,
+        denominator: i128,
+    ) {
+        assert!(from_token != to_token, "Same token");
+        assert!(numerator > 0 && denominator > 0, "Invalid ratio");
+        ownable::set_owner(e, &owner);
+        e.storage().instance().set(&FROM_TOKEN_KEY, &from_token);
+        e.storage().instance().set(&TO_TOKEN_KEY, &to_token);
+        e.storage()
+            .instance()
+            .set(&RATIO_KEY, &(numerator, denominator));
+    }
+
+    /// Get the token converted from.
+    pub fn from_token(e: &Env) -> Address {
+        e.storage()
+            .instance()
+            .get(&FROM_TOKEN_KEY)
+            .expect("From token not set")
+    }
+
+    /// Get the token converted to.
+    pub fn to_token(e: &Env) -> Address {
+        e.storage()
+            .instance()
+            .get(&TO_TOKEN_KEY)
+            .expect("To token not set")
+    }
+
+    /// Get the numerator and the denominator of the conversion ratio.
+    pub fn ratio(e: &Env) -> (i128, i128) {
+        e.storage()
+            .instance()
+            .get(&RATIO_KEY)
+            .expect("Ratio not set")
+    }
+
+    /// Get the progress of the migration.
+    pub fn progress(e: &Env) -> MigrationProgress {
+        e.storage()
+            .instance()
+            .get(&PROGRESS_KEY)
+            .unwrap_or_default()
+    }
+
+    /// Get the number of holders of the from token left to migrate, skipped holders excluded.
+    pub fn remaining_holders(e: &Env) -> u32 {
+        let (holder_count, skipped_holders) =
+            Self::skipped_tail(e, &TokenClient::new(e, &Self::from_token(e)));
+        holder_count - skipped_holders
+    }
+
+    fn skip_round(e: &Env) -> u32 {
+        e.storage().instance().get(&SKIP_ROUND_KEY).unwrap_or(0)
+    }
+
+    fn is_skipped(e: &Env, account: &Address, round: u32) -> bool {
+        let skipped_round: Option<u32> = e
+            .storage()
+            .persistent()
+            .get(&DataKey::Skipped(account.clone()));
+        skipped_round == Some(round)
+    }
+
+    fn set_skipped(e: &Env, account: &Address, round: u32) {
+        let key = DataKey::Skipped(account.clone());
+        e.storage().persistent().set(&key, &round);
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, SIXTY_DAY_LEDGERS, SIXTY_DAY_LEDGERS);
+    }
+
+    /// Get the number of holders of the from token and the number of skipped holders at the end of its holder set. The skipped holders are counted from the holder set rather than stored, since a skipped holder can still leave the set (e.g. by redeeming), which moves the last holder into its place.
+    fn skipped_tail(e: &Env, from_token: &TokenClient) -> (u32, u32) {
+        let holder_count = from_token.holder_count();
+        let round = Self::skip_round(e);
+        let mut skipped_holders = 0;
+        while skipped_holders < holder_count {
+            let limit = (holder_count - skipped_holders).min(HOLDERS_PAGE_SIZE);
+            let holders = from_token.holders(&(holder_count - skipped_holders - limit), &limit);
+            for (account, _) in holders.iter().rev() {
+                if !Self::is_skipped(e, &account, round) {
+                    return (holder_count, skipped_holders);
+                }
+                skipped_holders += 1;
+            }
+        }
+        (holder_count, skipped_holders)
+    }
+
+    /// Set the permission manager (central role management authority). It is used to check the WHITELISTED_ROLE of the holders on the to token.
+    ///
+    /// # Arguments
+    ///
+    /// * `permission_manager` - The address of the permission manager.
+    ///
+    /// # Errors
+    ///
+    /// The caller must be the owner.
+    ///
+    #[only_owner]
+    pub fn set_permission_manager(e: &Env, permission_manager: Address) {
+        e.storage()
+            .instance()
+            .set(&PERMISSION_MANAGER_KEY, &permission_manager);
+    }
+
+    fn permission_manager_client(e: &Env) -> PermissionManagerClient<'_> {
+        let permission_manager: Address = e
+            .storage()
+            .instance()
+            .get(&PERMISSION_MANAGER_KEY)
+            .expect("Permission manager not set");
+        PermissionManagerClient::new(e, &permission_manager)
+    }
+
+    /// Get the progress of the check of the holder set of the from token.
+    pub fn holder_check(e: &Env) -> HolderCheck {
+        e.storage()
+            .instance()
+            .get(&HOLDER_CHECK_KEY)
+            .unwrap_or_default()
+    }
+
+    /// Check a page of the holder set of the from token before the migration starts. Once every holder is checked, the balances of the holders must add up to the total supply: holders that were never synchronized by the from token would otherwise be left behind. It is called again until it returns true.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum number of holders to check.
+    ///
+    /// # Errors
+    ///
+    /// The caller must be the owner.
+    /// The check must not be completed.
+    /// The balances of the holders must add up to the total supply of the from token.
+    ///
+    #[only_owner]
+    pub fn check_holders(e: &Env, limit: u32) -> bool {
+        let from_token = TokenClient::new(e, &Self::from_token(e));
+        let mut check = Self::holder_check(e);
+        assert!(!check.completed, "Holders already checked");
+
+        let holders = from_token.holders(&check.checked_holders, &limit);
+        for (_, balance) in holders.iter() {
+            check.checked_amount = check.checked_amount.checked_add(balance).expect("Overflow");
+        }
+        check.checked_holders += holders.len();
+        if check.checked_holders == from_token.holder_count() {
+            assert!(
+                check.checked_amount == from_token.total_supply(),
+                "Holders not synced"
+            );
+            check.completed = true;
+        }
+        e.storage().instance().set(&HOLDER_CHECK_KEY, &check);
+        check.completed
+    }
+
+    /// Retry the skipped holders, e.g. once they have the WHITELISTED_ROLE on the to token. They are migrated by the next batches.
+    ///
+    /// # Errors
+    ///
+    /// The caller must be the owner.
+    ///
+    #[only_owner]
+    pub fn retry_skipped_holders(e: &Env) {
+        e.storage()
+            .instance()
+            .set(&SKIP_ROUND_KEY, &(Self::skip_round(e) + 1));
+        let mut progress = Self::progress(e);
+        progress.skipped_holders = 0;
+        e.storage().instance().set(&PROGRESS_KEY, &progress);
+    }
+
+    /// Migrate a batch of holders: their whole balance of the from token is burned and the converted amount, rounded down, is minted on the to token. Holders without the WHITELISTED_ROLE on the to token, or whose converted amount rounds down to zero, are skipped and keep their balance of the from token. Holders are taken from the end of the holder set of the from token, before the skipped holders: removing a holder moves the last skipped holder in its place, so the skipped holders stay at the end and the migration can be resumed by calling it again until no holder remains. The skipped holders at the end are counted again on each call; a skipped holder moved out of the end by a holder leaving the set is checked again when its turn comes. The from token should have its transfers and mints paused, and its pending redemptions settled, before the migration starts.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum number of holders to migrate. It is capped at 20.
+    ///
+    /// # Errors
+    ///
+    /// The caller must be the owner.
+    /// The holder set of the from token must be checked with `check_holders`.
+    /// The permission manager must be set.
+    /// The from token must still have holders that are not skipped.
+    ///
+    #[only_owner]
+    pub fn migrate(e: &Env, limit: u32) -> u32 {
+        assert!(Self::holder_check(e).completed, "Holders not checked");
+        let from_token = TokenClient::new(e, &Self::from_token(e));
+        let to_token_address = Self::to_token(e);
+        let to_token = TokenClient::new(e, &to_token_address);
+        let (numerator, denominator) = Self::ratio(e);
+        let migration_contract_address = e.current_contract_address();
+
+        let mut progress = Self::progress(e);
+        let round = Self::skip_round(e);
+        let (holder_count, skipped_holders) = Self::skipped_tail(e, &from_token);
+        let remaining_holders = holder_count - skipped_holders;
+        progress.skipped_holders = skipped_holders;
+        assert!(remaining_holders > 0, "Migration completed");
+        let batch_size = limit.min(MAX_BATCH_SIZE).min(remaining_holders);
+        let holders = from_token.holders(&(remaining_holders - batch_size), &batch_size);
+        let mut accounts = Vec::new(e);
+        for (account, _) in holders.iter() {
+            accounts.push_back(account);
+        }
+        let whitelisted = Self::permission_manager_client(e).has_scoped_roles(
+            &accounts,
+            &WHITELISTED_ROLE,
+            &to_token_address,
+        );
+
+        let batch = progress.batches;
+        for index in (0..holders.len()).rev() {
+            let (account, balance) = holders.get_unchecked(index);
+            let converted_amount = balance.checked_mul(numerator).expect("Overflow") / denominator;
+            if !whitelisted.get_unchecked(index) || converted_amount == 0 {
+                Self::set_skipped(e, &account, round);
+                progress.skipped_holders += 1;
+                e.events().publish(
+                    (MIGRATION_EVENT, HOLDER_SKIPPED_EVENT),
+                    (batch, account, balance),
+                );
+                continue;
+            }
+            from_token.burn(&account, &balance, &migration_contract_address);
+            to_token.mint(&account, &converted_amount, &migration_contract_address);
+
+            progress.migrated_holders += 1;
+            progress.burned_amount += balance;
+            progress.minted_amount += converted_amount;
+            e.events().publish(
+                (MIGRATION_EVENT, HOLDER_MIGRATED_EVENT),
+                (batch, account, balance, converted_amount),
+            );
+        }
+        progress.batches += 1;
+        e.storage().instance().set(&PROGRESS_KEY, &progress);
+
+        e.events().publish(
+            (MIGRATION_EVENT, BATCH_MIGRATED_EVENT),
+            (batch, batch_size, remaining_holders - batch_size),
+        );
+        batch_size
+    }
+}
+
+#[default_impl]
+#[contractimpl]
+impl Ownable for Migration {}
@@ -0,0 +1,507 @@
+#![cfg(test)]
+
+extern crate std;
+
+use super::contract::{HolderCheck, Migration, MigrationClient, MigrationProgress};
+use contracts_utils::role::{BURNER_ROLE, MINTER_ROLE, WHITELISTED_ROLE};
+use soroban_sdk::{testutils::Address as _, Address, Env, String, Vec};
+
+mod permission_manager {
+    use soroban_sdk::contractimport;
+
+    contractimport!(file = "../../wasm/permission_manager.wasm");
+}
+
+mod permission_manager_mock {
+    use soroban_sdk::{contract, contractimpl, Address, Env, Symbol, Vec};
+
+    // Every account has the WHITELISTED_ROLE unless it is blocked.
+    #[contract]
+    pub struct Mock;
+
+    #[contractimpl]
+    impl Mock {
+        pub fn has_scoped_roles(
+            e: Env,
+            accounts: Vec<Address>,
+            _role: Symbol,
+            _scope: Address,
+        ) -> Vec<bool> {
+            let mut has_roles = Vec::new(&e);
+            for account in &accounts {
+                has_roles.push_back(!e.storage().instance().has(&account));
+            }
+            has_roles
+        }
+
+        pub fn set_blocked(e: Env, account: Address, blocked: bool) {
+            if blocked {
+                e.storage().instance().set(&account, &true);
+            } else {
+                e.storage().instance().remove(&account);
+            }
+        }
+    }
+}
+
+mod token_contract {
+    use soroban_sdk::{contractclient, Address, Env};
+
+    pub const WASM: &[u8] = include_bytes!("../../../wasm/token.wasm");
+
+    #[contractclient(name = "Client")]
+    pub trait TokenInterface {
+        fn set_permission_manager(e: Env, permission_manager: Address);
+        fn mint(e: Env, account: Address, amount: i128, caller: Address);
+        fn balance(e: Env, account: Address) -> i128;
+        fn total_supply(e: Env) -> i128;
+    }
+}
+
+mod token {
+    use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env, Symbol, Vec};
+
+    use crate::contract::TokenInterface;
+
+    const HOLDERS_KEY: Symbol = symbol_short!("HOLDERS");
+    const UNSYNCED_KEY: Symbol = symbol_short!("UNSYNCED");
+
+    // Keeps the holders in a single list and swap-removes them like the token does.
+    #[contract]
+    pub struct Mock;
+
+    fn all_holders(e: &Env) -> Vec<(Address, i128)> {
+        e.storage()
+            .instance()
+            .get(&HOLDERS_KEY)
+            .unwrap_or(Vec::new(e))
+    }
+
+    fn index_of(holders: &Vec<(Address, i128)>, account: &Address) -> Option<u32> {
+        holders
+            .iter()
+            .position(|(holder, _)| holder == *account)
+            .map(|index| index as u32)
+    }
+
+    #[contractimpl]
+    impl TokenInterface for Mock {
+        fn total_supply(e: Env) -> i128 {
+            let unsynced_supply: i128 = e.storage().instance().get(&UNSYNCED_KEY).unwrap_or(0);
+            all_holders(&e)
+                .iter()
+                .fold(unsynced_supply, |total_supply, (_, balance)| {
+                    total_supply + balance
+                })
+        }
+
+        fn holder_count(e: Env) -> u32 {
+            all_holders(&e).len()
+        }
+
+        fn holders(e: Env, cursor: u32, limit: u32) -> Vec<(Address, i128)> {
+            let holders = all_holders(&e);
+            holders.slice(cursor..(cursor + limit).min(holders.len()))
+        }
+
+        fn burn(e: Env, account: Address, amount: i128, _caller: Address) {
+            let mut holders = all_holders(&e);
+            let index = index_of(&holders, &account).expect("Not a holder");
+            let (_, balance) = holders.get(index).unwrap();
+            assert!(balance >= amount, "Insufficient balance");
+            if balance == amount {
+                let last = holders.pop_back().unwrap();
+                if index < holders.len() {
+                    holders.set(index, last);
+                }
+            } else {
+                holders.set(index, (account, balance - amount));
+            }
+            e.storage().instance().set(&HOLDERS_KEY, &holders);
+        }
+
+        fn mint(e: Env, account: Address, amount: i128, _caller: Address) {
+            let mut holders = all_holders(&e);
+            match index_of(&holders, &account) {
+                Some(index) => {
+                    let (_, balance) = holders.get(index).unwrap();
+                    holders.set(index, (account, balance + amount));
+                }
+                None => holders.push_back((account, amount)),
+            }
+            e.storage().instance().set(&HOLDERS_KEY, &holders);
+        }
+    }
+
+    #[contractimpl]
+    impl Mock {
+        pub fn balance(e: Env, account: Address) -> i128 {
+            let holders = all_holders(&e);
+            index_of(&holders, &account)
+                .map(|index| holders.get(index).unwrap().1)
+                .unwrap_or(0)
+        }
+
+        // Mints to an account that is not in the holder set, like a holder never synchronized.
+        pub fn mint_unsynced(e: Env, amount: i128) {
+            let unsynced_supply: i128 = e.storage().instance().get(&UNSYNCED_KEY).unwrap_or(0);
+            e.storage()
+                .instance()
+                .set(&UNSYNCED_KEY, &(unsynced_supply + amount));
+        }
+    }
+}
+
+fn setup_env() -> Env {
+    let e: Env = Env::default();
+    e.mock_all_auths();
+    e
+}
+
+fn deploy_migration(
+    e: &Env,
+    numerator: i128,
+    denominator: i128,
+) -> (
+    Address,
+    token::MockClient<'_>,
+    token::MockClient<'_>,
+    permission_manager_mock::MockClient<'_>,
+    MigrationClient<'_>,
+) {
+    let owner: Address = Address::generate(e);
+    let from_token = token::MockClient::new(e, &e.register(token::Mock, ()));
+    let to_token = token::MockClient::new(e, &e.register(token::Mock, ()));
+    let migration_address = e.register(
+        Migration,
+        (
+            owner.clone(),
+            from_token.address.clone(),
+            to_token.address.clone(),
+            numerator,
+            denominator,
+        ),
+    );
+    let client = MigrationClient::new(e, &migration_address);
+    let permission_manager =
+        permission_manager_mock::MockClient::new(e, &e.register(permission_manager_mock::Mock, ()));
+    client.set_permission_manager(&permission_manager.address);
+
+    (owner, from_token, to_token, permission_manager, client)
+}
+
+fn mint_holders(e: &Env, token: &token::MockClient<'_>, count: u32) -> Vec<Address> {
+    let mut holders = Vec::new(e);
+    for index in 0..count {
+        let holder: Address = Address::generate(e);
+        token.mint(&holder, &(100 * (index as i128 + 1)), &holder);
+        holders.push_back(holder);
+    }
+    holders
+}
+
+#[test]
+fn test_should_set_tokens_and_ratio_on_constructor() {
+    let e = setup_env();
+    let (owner, from_token, to_token, _, client) = deploy_migration(&e, 3, 2);
+
+    assert_eq!(client.get_owner(), Some(owner));
+    assert_eq!(client.from_token(), from_token.address);
+    assert_eq!(client.to_token(), to_token.address);
+    assert_eq!(client.ratio(), (3, 2));
+    assert_eq!(client.progress(), MigrationProgress::default());
+}
+
+#[test]
+#[should_panic]
+fn test_constructor_should_panic_if_ratio_is_invalid() {
+    let e = setup_env();
+
+    deploy_migration(&e, 1, 0);
+}
+
+#[test]
+fn test_migrate_should_require_owner_auth() {
+    let e = setup_env();
+    let (owner, from_token, _, _, client) = deploy_migration(&e, 1, 1);
+    mint_holders(&e, &from_token, 1);
+    client.check_holders(&10);
+
+    client.migrate(&10);
+
+    let auths = e.auths();
+    assert_eq!(auths.len(), 1);
+    let (addr, _invocation) = &auths[0];
+    assert_eq!(addr, &owner);
+}
+
+#[test]
+fn test_migrate_should_convert_every_holder_across_batches() {
+    let e = setup_env();
+    let (_, from_token, to_token, _, client) = deploy_migration(&e, 3, 2);
+    let holders = mint_holders(&e, &from_token, 5);
+    client.check_holders(&10);
+
+    assert_eq!(client.migrate(&2), 2);
+    assert_eq!(client.remaining_holders(), 3);
+    assert_eq!(client.migrate(&2), 2);
+    assert_eq!(client.migrate(&2), 1);
+
+    assert_eq!(client.remaining_holders(), 0);
+    for (index, holder) in holders.iter().enumerate() {
+        let balance = 100 * (index as i128 + 1);
+        assert_eq!(from_token.balance(&holder), 0);
+        assert_eq!(to_token.balance(&holder), balance * 3 / 2);
+    }
+    assert_eq!(
+        client.progress(),
+        MigrationProgress {
+            batches: 3,
+            migrated_holders: 5,
+            burned_amount: 1500,
+            minted_amount: 2250,
+            skipped_holders: 0,
+        }
+    );
+    let result = client.try_migrate(&2);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_migrate_should_round_down_the_converted_amount() {
+    let e = setup_env();
+    let (_, from_token, to_token, _, client) = deploy_migration(&e, 1, 3);
+    let holder: Address = Address::generate(&e);
+    from_token.mint(&holder, &100, &holder);
+    client.check_holders(&10);
+
+    client.migrate(&1);
+
+    assert_eq!(from_token.balance(&holder), 0);
+    assert_eq!(to_token.balance(&holder), 33);
+    assert_eq!(client.progress().minted_amount, 33);
+}
+
+#[test]
+fn test_check_holders_should_check_the_holder_set_across_pages() {
+    let e = setup_env();
+    let (_, from_token, _, _, client) = deploy_migration(&e, 1, 1);
+    mint_holders(&e, &from_token, 3);
+    let result = client.try_migrate(&10);
+    assert!(result.is_err());
+
+    assert!(!client.check_holders(&2));
+    assert!(client.check_holders(&2));
+
+    assert_eq!(
+        client.holder_check(),
+        HolderCheck {
+            checked_holders: 3,
+            checked_amount: 600,
+            completed: true,
+        }
+    );
+    let result = client.try_check_holders(&2);
+    assert!(result.is_err());
+    assert_eq!(client.migrate(&10), 3);
+}
+
+#[test]
+fn test_check_holders_should_fail_if_a_holder_is_not_synced() {
+    let e = setup_env();
+    let (_, from_token, _, _, client) = deploy_migration(&e, 1, 1);
+    mint_holders(&e, &from_token, 3);
+    from_token.mint_unsynced(&50);
+
+    let result = client.try_check_holders(&10);
+
+    assert!(result.is_err());
+    assert_eq!(client.holder_check(), HolderCheck::default());
+    let result = client.try_migrate(&10);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_migrate_should_skip_holders_not_whitelisted_on_the_to_token() {
+    let e = setup_env();
+    let (_, from_token, to_token, permission_manager, client) = deploy_migration(&e, 1, 1);
+    let holders = mint_holders(&e, &from_token, 5);
+    let skipped_holder = holders.get_unchecked(1);
+    permission_manager.set_blocked(&skipped_holder, &true);
+    client.check_holders(&10);
+
+    assert_eq!(client.migrate(&2), 2);
+    assert_eq!(client.migrate(&2), 2);
+    assert_eq!(client.migrate(&2), 1);
+
+    assert_eq!(client.remaining_holders(), 0);
+    assert_eq!(client.progress().skipped_holders, 1);
+    assert_eq!(client.progress().migrated_holders, 4);
+    assert_eq!(from_token.balance(&skipped_holder), 200);
+    assert_eq!(to_token.balance(&skipped_holder), 0);
+    for holder in holders.iter().filter(|holder| *holder != skipped_holder) {
+        assert_eq!(from_token.balance(&holder), 0);
+    }
+    let result = client.try_migrate(&2);
+    assert!(result.is_err());
+
+    permission_manager.set_blocked(&skipped_holder, &false);
+    client.retry_skipped_holders();
+    assert_eq!(client.migrate(&2), 1);
+
+    assert_eq!(from_token.balance(&skipped_holder), 0);
+    assert_eq!(to_token.balance(&skipped_holder), 200);
+    assert_eq!(client.progress().migrated_holders, 5);
+    assert_eq!(client.progress().skipped_holders, 0);
+}
+
+#[test]
+fn test_migrate_should_count_the_skipped_holders_again_when_one_leaves_the_holder_set() {
+    let e = setup_env();
+    let (_, from_token, to_token, permission_manager, client) = deploy_migration(&e, 1, 1);
+    let holders = mint_holders(&e, &from_token, 5);
+    let skipped_holder = holders.get_unchecked(4);
+    permission_manager.set_blocked(&skipped_holder, &true);
+    client.check_holders(&10);
+    assert_eq!(client.migrate(&2), 2);
+    assert_eq!(client.progress().skipped_holders, 1);
+    assert_eq!(client.remaining_holders(), 3);
+
+    from_token.burn(&skipped_holder, &500, &skipped_holder);
+
+    assert_eq!(client.remaining_holders(), 3);
+    assert_eq!(client.migrate(&2), 2);
+    assert_eq!(client.migrate(&2), 1);
+    assert_eq!(client.remaining_holders(), 0);
+    assert_eq!(client.progress().skipped_holders, 0);
+    assert_eq!(client.progress().migrated_holders, 4);
+    for holder in holders.iter().filter(|holder| *holder != skipped_holder) {
+        assert_eq!(from_token.balance(&holder), 0);
+        assert!(to_token.balance(&holder) > 0);
+    }
+    let result = client.try_migrate(&2);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_migrate_should_check_again_a_skipped_holder_moved_out_of_the_end_of_the_holder_set() {
+    let e = setup_env();
+    let (_, from_token, to_token, permission_manager, client) = deploy_migration(&e, 1, 1);
+    let holders = mint_holders(&e, &from_token, 5);
+    let skipped_holder = holders.get_unchecked(4);
+    let leaving_holder = holders.get_unchecked(1);
+    permission_manager.set_blocked(&skipped_holder, &true);
+    client.check_holders(&10);
+    assert_eq!(client.migrate(&2), 2);
+
+    from_token.burn(&leaving_holder, &200, &leaving_holder);
+
+    assert_eq!(client.remaining_holders(), 3);
+    assert_eq!(client.migrate(&10), 3);
+    assert_eq!(client.remaining_holders(), 0);
+    assert_eq!(client.progress().skipped_holders, 1);
+    assert_eq!(client.progress().migrated_holders, 3);
+    assert_eq!(from_token.balance(&skipped_holder), 500);
+    assert_eq!(to_token.balance(&holders.get_unchecked(0)), 100);
+    assert_eq!(to_token.balance(&holders.get_unchecked(2)), 300);
+}
+
+#[test]
+fn test_migrate_should_skip_holders_whose_converted_amount_rounds_down_to_zero() {
+    let e = setup_env();
+    let (_, from_token, to_token, _, client) = deploy_migration(&e, 1, 250);
+    let holders = mint_holders(&e, &from_token, 3);
+    client.check_holders(&10);
+
+    assert_eq!(client.migrate(&10), 3);
+
+    assert_eq!(client.remaining_holders(), 0);
+    assert_eq!(client.progress().skipped_holders, 2);
+    assert_eq!(from_token.balance(&holders.get_unchecked(0)), 100);
+    assert_eq!(from_token.balance(&holders.get_unchecked(1)), 200);
+    assert_eq!(from_token.balance(&holders.get_unchecked(2)), 0);
+    assert_eq!(to_token.balance(&holders.get_unchecked(2)), 1);
+    assert_eq!(client.progress().burned_amount, 300);
+}
+
+#[test]
+fn test_migrate_should_convert_the_holders_of_a_token() {
+    let e = setup_env();
+    let owner: Address = Address::generate(&e);
+    let admin: Address = Address::generate(&e);
+    let minter: Address = Address::generate(&e);
+    let permission_manager_address = e.register(
+        permission_manager::WASM,
+        permission_manager::Args::__constructor(&admin),
+    );
+    let permission_manager = permission_manager::Client::new(&e, &permission_manager_address);
+    let deploy_token = |symbol: &str| {
+        let token_address = e.register(
+            token_contract::WASM,
+            (
+                owner.clone(),
+                String::from_str(&e, "Token"),
+                String::from_str(&e, symbol),
+                5_u32,
+            ),
+        );
+        let token = token_contract::Client::new(&e, &token_address);
+        token.set_permission_manager(&permission_manager_address);
+        token
+    };
+    let from_token = deploy_token("EUTBL");
+    let to_token = deploy_token("eurUSTBL");
+    let migration_address = e.register(
+        Migration,
+        (
+            owner.clone(),
+            from_token.address.clone(),
+            to_token.address.clone(),
+            3_i128,
+            2_i128,
+        ),
+    );
+    let client = MigrationClient::new(&e, &migration_address);
+    client.set_permission_manager(&permission_manager_address);
+    permission_manager.grant_role(&admin, &minter, &MINTER_ROLE);
+    permission_manager.grant_role(&admin, &migration_address, &MINTER_ROLE);
+    permission_manager.grant_role(&admin, &migration_address, &BURNER_ROLE);
+    let mut holders = Vec::new(&e);
+    for index in 0..3 {
+        let holder: Address = Address::generate(&e);
+        permission_manager.grant_role(&admin, &holder, &WHITELISTED_ROLE);
+        from_token.mint(&holder, &(100 * (index + 1)), &minter);
+        holders.push_back(holder);
+    }
+    let skipped_holder: Address = Address::generate(&e);
+    permission_manager.grant_scoped_role(
+        &admin,
+        &skipped_holder,
+        &WHITELISTED_ROLE,
+        &from_token.address,
+    );
+    from_token.mint(&skipped_holder, &1000, &minter);
+
+    assert!(client.check_holders(&10));
+    assert_eq!(client.migrate(&2), 2);
+    assert_eq!(client.migrate(&2), 2);
+
+    assert_eq!(client.remaining_holders(), 0);
+    for (index, holder) in holders.iter().enumerate() {
+        assert_eq!(from_token.balance(&holder), 0);
+        assert_eq!(to_token.balance(&holder), 150 * (index as i128 + 1));
+    }
+    assert_eq!(from_token.balance(&skipped_holder), 1000);
+    assert_eq!(from_token.total_supply(), 1000);
+    assert_eq!(to_token.total_supply(), 900);
+    assert_eq!(
+        client.progress(),
+        MigrationProgress {
+            batches: 2,
+            migrated_holders: 3,
+            burned_amount: 600,
+            minted_amount: 900,
+            skipped_holders: 1,
+        }
+    );
+}
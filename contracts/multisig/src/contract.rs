@@ -0,0 +1,278 @@
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short, Address, Env, Symbol, Val, Vec,
+};
+
+#[contract]
+pub struct Multisig;
+
+pub const SIGNERS_KEY: Symbol = symbol_short!("SIGNERS");
+pub const THRESHOLD_KEY: Symbol = symbol_short!("THRESH");
+pub const PROPOSAL_COUNT_KEY: Symbol = symbol_short!("COUNT");
+
+pub const PROPOSAL_EVENT: Symbol = symbol_short!("PROPOSAL");
+pub const PROPOSAL_CREATED_EVENT: Symbol = symbol_short!("CREATE");
+pub const PROPOSAL_APPROVED_EVENT: Symbol = symbol_short!("APPROVE");
+pub const PROPOSAL_EXECUTED_EVENT: Symbol = symbol_short!("EXECUTE");
+pub const PROPOSAL_CANCELED_EVENT: Symbol = symbol_short!("CANCEL");
+pub const SIGNERS_UPDATED_EVENT: Symbol = symbol_short!("SIGNERS");
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Proposal(u32),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ProposalAction {
+    /// Invoke a function of a contract (e.g. `set_permission_manager`, `add_token`, `upgrade`) with the given arguments.
+    Invoke(Address, Symbol, Vec<Val>),
+    /// Replace the signers and the threshold of the multisig.
+    SetSigners(Vec<Address>, u32),
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProposalStatus {
+    Pending,
+    Executed,
+    Canceled,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proposal {
+    pub proposer: Address,
+    pub action: ProposalAction,
+    pub approvals: Vec<Address>,
+    pub status: ProposalStatus,
+    pub expires_at: u32,
+}
+
+const ONE_DAY_LEDGERS: u32 = 17_280;
+const SIXTY_DAY_LEDGERS: u32 = ONE_DAY_LEDGERS * 60;
+// Approvals older than this should not be able to execute, e.g. after the signers rotate back.
+const PROPOSAL_LIFETIME_LEDGERS: u32 = ONE_DAY_LEDGERS * 7;
+
+#[contractimpl]
+impl Multisig {
+    pub fn __constructor(e: &Env, signers: Vec<Address>, threshold: u32) {
+        Self::set_signers(e, &signers, threshold);
+    }
+
+    fn set_signers(e: &Env, signers: &Vec<Address>, threshold: u32) {
+        for (index, signer) in signers.iter().enumerate() {
+            assert!(
+                signers.first_index_of(&signer) == Some(index as u32),
+                "Duplicate signer"
+            );
+        }
+        assert!(
+            threshold > 0 && threshold <= signers.len(),
+            "Invalid threshold"
+        );
+        e.storage().instance().set(&SIGNERS_KEY, signers);
+        e.storage().instance().set(&THRESHOLD_KEY, &threshold);
+    }
+
+    fn assert_signer(e: &Env, account: &Address) {
+        assert!(Self::signers(e).contains(account), "Not a signer");
+    }
+
+    fn set_proposal(e: &Env, proposal_id: u32, proposal: &Proposal) {
+        let key = DataKey::Proposal(proposal_id);
+        e.storage().persistent().set(&key, proposal);
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, SIXTY_DAY_LEDGERS, SIXTY_DAY_LEDGERS);
+    }
+
+    fn pending_proposal(e: &Env, proposal_id: u32) -> Proposal {
+        let proposal = Self::get_proposal(e, proposal_id);
+        assert!(
+            proposal.status == ProposalStatus::Pending,
+            "Proposal not pending"
+        );
+        proposal
+    }
+
+    fn open_proposal(e: &Env, proposal_id: u32) -> Proposal {
+        let proposal = Self::pending_proposal(e, proposal_id);
+        assert!(
+            e.ledger().sequence() <= proposal.expires_at,
+            "Proposal expired"
+        );
+        proposal
+    }
+
+    /// Get the signers of the multisig.
+    pub fn signers(e: &Env) -> Vec<Address> {
+        e.storage()
+            .instance()
+            .get(&SIGNERS_KEY)
+            .expect("Signers not set")
+    }
+
+    /// Get the number of approvals required to execute a proposal.
+    pub fn threshold(e: &Env) -> u32 {
+        e.storage()
+            .instance()
+            .get(&THRESHOLD_KEY)
+            .expect("Threshold not set")
+    }
+
+    /// Get the number of proposals created so far.
+    pub fn proposal_count(e: &Env) -> u32 {
+        e.storage().instance().get(&PROPOSAL_COUNT_KEY).unwrap_or(0)
+    }
+
+    /// Get a proposal.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal_id` - The proposal ID.
+    pub fn get_proposal(e: &Env, proposal_id: u32) -> Proposal {
+        e.storage()
+            .persistent()
+            .get(&DataKey::Proposal(proposal_id))
+            .expect("Proposal not found")
+    }
+
+    /// Propose an action. The proposal is approved by its proposer and expires after 7 days.
+    ///
+    /// # Arguments
+    ///
+    /// * `signer` - The address of the signer making the proposal.
+    /// * `action` - The action to execute once the threshold is reached.
+    ///
+    /// # Errors
+    ///
+    /// The signer must be one of the signers of the multisig.
+    ///
+    pub fn propose(e: &Env, signer: Address, action: ProposalAction) -> u32 {
+        signer.require_auth();
+        Self::assert_signer(e, &signer);
+
+        let proposal_id = Self::proposal_count(e);
+        e.storage()
+            .instance()
+            .set(&PROPOSAL_COUNT_KEY, &(proposal_id + 1));
+
+        let proposal = Proposal {
+            proposer: signer.clone(),
+            action,
+            approvals: Vec::from_array(e, [signer]),
+            status: ProposalStatus::Pending,
+            expires_at: e.ledger().sequence() + PROPOSAL_LIFETIME_LEDGERS,
+        };
+        Self::set_proposal(e, proposal_id, &proposal);
+
+        e.events().publish(
+            (PROPOSAL_EVENT, PROPOSAL_CREATED_EVENT),
+            (proposal_id, proposal),
+        );
+        proposal_id
+    }
+
+    /// Approve a pending proposal.
+    ///
+    /// # Arguments
+    ///
+    /// * `signer` - The address of the approving signer.
+    /// * `proposal_id` - The proposal ID.
+    ///
+    /// # Errors
+    ///
+    /// The signer must be one of the signers of the multisig.
+    /// The proposal must be pending and not expired.
+    /// The signer must not have approved the proposal already.
+    ///
+    pub fn approve(e: &Env, signer: Address, proposal_id: u32) {
+        signer.require_auth();
+        Self::assert_signer(e, &signer);
+
+        let mut proposal = Self::open_proposal(e, proposal_id);
+        assert!(!proposal.approvals.contains(&signer), "Already approved");
+        proposal.approvals.push_back(signer.clone());
+        Self::set_proposal(e, proposal_id, &proposal);
+
+        e.events().publish(
+            (PROPOSAL_EVENT, PROPOSAL_APPROVED_EVENT),
+            (proposal_id, signer),
+        );
+    }
+
+    /// Execute a pending proposal once it has been approved by enough signers. Approvals of accounts that are no longer signers are not counted.
+    ///
+    /// # Arguments
+    ///
+    /// * `signer` - The address of the executing signer.
+    /// * `proposal_id` - The proposal ID.
+    ///
+    /// # Errors
+    ///
+    /// The signer must be one of the signers of the multisig.
+    /// The proposal must be pending and not expired.
+    /// The proposal must be approved by at least `threshold` signers.
+    ///
+    pub fn execute(e: &Env, signer: Address, proposal_id: u32) {
+        signer.require_auth();
+        Self::assert_signer(e, &signer);
+
+        let mut proposal = Self::open_proposal(e, proposal_id);
+        let signers = Self::signers(e);
+        let approvals = proposal
+            .approvals
+            .iter()
+            .filter(|approver| signers.contains(approver))
+            .count() as u32;
+        assert!(approvals >= Self::threshold(e), "Threshold not reached");
+
+        proposal.status = ProposalStatus::Executed;
+        Self::set_proposal(e, proposal_id, &proposal);
+
+        match proposal.action {
+            ProposalAction::Invoke(contract, function, args) => {
+                e.invoke_contract::<Val>(&contract, &function, args);
+            }
+            ProposalAction::SetSigners(signers, threshold) => {
+                Self::set_signers(e, &signers, threshold);
+                e.events()
+                    .publish((SIGNERS_UPDATED_EVENT,), (signers, threshold));
+            }
+        }
+
+        e.events().publish(
+            (PROPOSAL_EVENT, PROPOSAL_EXECUTED_EVENT),
+            (proposal_id, signer),
+        );
+    }
+
+    /// Cancel a pending proposal, expired or not.
+    ///
+    /// # Arguments
+    ///
+    /// * `signer` - The address of the proposer.
+    /// * `proposal_id` - The proposal ID.
+    ///
+    /// # Errors
+    ///
+    /// The signer must still be one of the signers of the multisig.
+    /// The signer must be the proposer of the proposal.
+    /// The proposal must be pending.
+    ///
+    pub fn cancel(e: &Env, signer: Address, proposal_id: u32) {
+        signer.require_auth();
+        Self::assert_signer(e, &signer);
+
+        let mut proposal = Self::pending_proposal(e, proposal_id);
+        assert!(proposal.proposer == signer, "Not the proposer");
+        proposal.status = ProposalStatus::Canceled;
+        Self::set_proposal(e, proposal_id, &proposal);
+
+        e.events().publish(
+            (PROPOSAL_EVENT, PROPOSAL_CANCELED_EVENT),
+            (proposal_id, signer),
+        );
+    }
+}
@@ -0,0 +1,267 @@
+#![cfg(test)]
+
+extern crate std;
+
+use super::contract::{Multisig, MultisigClient, ProposalAction, ProposalStatus};
+use contracts_utils::role::MINTER_ROLE;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env, IntoVal, Symbol, Vec,
+};
+
+mod permission_manager {
+    use soroban_sdk::contractimport;
+
+    contractimport!(file = "../../wasm/permission_manager.wasm");
+}
+
+fn setup_env() -> Env {
+    let e: Env = Env::default();
+    e.mock_all_auths();
+    e
+}
+
+fn deploy_multisig(e: &Env, threshold: u32) -> (Vec<Address>, MultisigClient<'_>) {
+    let signers = Vec::from_array(
+        e,
+        [
+            Address::generate(e),
+            Address::generate(e),
+            Address::generate(e),
+        ],
+    );
+    let multisig_address = e.register(Multisig, (signers.clone(), threshold));
+    let client = MultisigClient::new(e, &multisig_address);
+
+    (signers, client)
+}
+
+fn deploy_permission_manager<'a>(e: &Env, admin: &Address) -> permission_manager::Client<'a> {
+    let permission_manager_address = e.register(
+        permission_manager::WASM,
+        permission_manager::Args::__constructor(admin),
+    );
+    permission_manager::Client::new(e, &permission_manager_address)
+}
+
+fn grant_minter_action(
+    e: &Env,
+    multisig: &MultisigClient<'_>,
+    permission_manager: &permission_manager::Client<'_>,
+    account: &Address,
+) -> ProposalAction {
+    ProposalAction::Invoke(
+        permission_manager.address.clone(),
+        Symbol::new(e, "grant_role"),
+        (multisig.address.clone(), account.clone(), MINTER_ROLE).into_val(e),
+    )
+}
+
+#[test]
+fn test_should_set_signers_and_threshold_on_constructor() {
+    let e = setup_env();
+    let (signers, client) = deploy_multisig(&e, 2);
+
+    assert_eq!(client.signers(), signers);
+    assert_eq!(client.threshold(), 2);
+}
+
+#[test]
+#[should_panic]
+fn test_constructor_should_panic_if_threshold_is_invalid() {
+    let e = setup_env();
+    let signers = Vec::from_array(&e, [Address::generate(&e)]);
+
+    e.register(Multisig, (signers, 2_u32));
+}
+
+#[test]
+fn test_propose_should_record_the_proposal_approved_by_the_proposer() {
+    let e = setup_env();
+    let (signers, client) = deploy_multisig(&e, 2);
+    let proposer = signers.get(0).unwrap();
+    let action = ProposalAction::SetSigners(signers.clone(), 1);
+
+    let proposal_id = client.propose(&proposer, &action);
+
+    let proposal = client.get_proposal(&proposal_id);
+    assert_eq!(proposal.proposer, proposer.clone());
+    assert_eq!(proposal.action, action);
+    assert_eq!(proposal.approvals, Vec::from_array(&e, [proposer]));
+    assert_eq!(proposal.status, ProposalStatus::Pending);
+    assert_eq!(client.proposal_count(), 1);
+}
+
+#[test]
+fn test_propose_should_fail_if_caller_is_not_a_signer() {
+    let e = setup_env();
+    let (signers, client) = deploy_multisig(&e, 2);
+    let stranger: Address = Address::generate(&e);
+
+    let result = client.try_propose(&stranger, &ProposalAction::SetSigners(signers, 1));
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_approve_should_fail_if_already_approved() {
+    let e = setup_env();
+    let (signers, client) = deploy_multisig(&e, 2);
+    let proposer = signers.get(0).unwrap();
+    let proposal_id = client.propose(&proposer, &ProposalAction::SetSigners(signers, 1));
+
+    let result = client.try_approve(&proposer, &proposal_id);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_execute_should_invoke_the_target_contract_once_threshold_is_reached() {
+    let e = setup_env();
+    let (signers, client) = deploy_multisig(&e, 2);
+    let permission_manager = deploy_permission_manager(&e, &client.address);
+    let account: Address = Address::generate(&e);
+    let action = grant_minter_action(&e, &client, &permission_manager, &account);
+    let proposal_id = client.propose(&signers.get(0).unwrap(), &action);
+    client.approve(&signers.get(1).unwrap(), &proposal_id);
+
+    client.execute(&signers.get(2).unwrap(), &proposal_id);
+
+    assert!(permission_manager
+        .has_role(&account, &MINTER_ROLE)
+        .is_some());
+    assert_eq!(
+        client.get_proposal(&proposal_id).status,
+        ProposalStatus::Executed
+    );
+}
+
+#[test]
+fn test_execute_should_fail_if_threshold_is_not_reached() {
+    let e = setup_env();
+    let (signers, client) = deploy_multisig(&e, 2);
+    let permission_manager = deploy_permission_manager(&e, &client.address);
+    let account: Address = Address::generate(&e);
+    let action = grant_minter_action(&e, &client, &permission_manager, &account);
+    let proposal_id = client.propose(&signers.get(0).unwrap(), &action);
+
+    let result = client.try_execute(&signers.get(0).unwrap(), &proposal_id);
+
+    assert!(result.is_err());
+    assert!(permission_manager
+        .has_role(&account, &MINTER_ROLE)
+        .is_none());
+}
+
+#[test]
+fn test_execute_should_fail_if_proposal_already_executed() {
+    let e = setup_env();
+    let (signers, client) = deploy_multisig(&e, 1);
+    let proposal_id = client.propose(
+        &signers.get(0).unwrap(),
+        &ProposalAction::SetSigners(signers.clone(), 1),
+    );
+    client.execute(&signers.get(0).unwrap(), &proposal_id);
+
+    let result = client.try_execute(&signers.get(0).unwrap(), &proposal_id);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_execute_should_update_signers_and_ignore_approvals_of_removed_signers() {
+    let e = setup_env();
+    let (signers, client) = deploy_multisig(&e, 2);
+    let new_signers = Vec::from_array(&e, [signers.get(1).unwrap(), signers.get(2).unwrap()]);
+    let pending_id = client.propose(
+        &signers.get(0).unwrap(),
+        &ProposalAction::SetSigners(signers.clone(), 1),
+    );
+    client.approve(&signers.get(1).unwrap(), &pending_id);
+    let proposal_id = client.propose(
+        &signers.get(1).unwrap(),
+        &ProposalAction::SetSigners(new_signers.clone(), 2),
+    );
+    client.approve(&signers.get(2).unwrap(), &proposal_id);
+
+    client.execute(&signers.get(1).unwrap(), &proposal_id);
+
+    assert_eq!(client.signers(), new_signers);
+    assert_eq!(client.threshold(), 2);
+    let result = client.try_execute(&signers.get(1).unwrap(), &pending_id);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_execute_should_fail_if_proposal_expired() {
+    let e = setup_env();
+    e.ledger()
+        .with_mut(|li| li.min_persistent_entry_ttl = 30 * 17_280);
+    let (signers, client) = deploy_multisig(&e, 2);
+    let proposal_id = client.propose(
+        &signers.get(0).unwrap(),
+        &ProposalAction::SetSigners(signers.clone(), 1),
+    );
+    let expires_at = client.get_proposal(&proposal_id).expires_at;
+    assert_eq!(expires_at, e.ledger().sequence() + 7 * 17_280);
+    client.approve(&signers.get(1).unwrap(), &proposal_id);
+    e.ledger().set_sequence_number(expires_at + 1);
+
+    let result = client.try_execute(&signers.get(0).unwrap(), &proposal_id);
+    assert!(result.is_err());
+    let result = client.try_approve(&signers.get(2).unwrap(), &proposal_id);
+    assert!(result.is_err());
+
+    client.cancel(&signers.get(0).unwrap(), &proposal_id);
+    assert_eq!(
+        client.get_proposal(&proposal_id).status,
+        ProposalStatus::Canceled
+    );
+}
+
+#[test]
+fn test_cancel_should_fail_if_proposer_is_no_longer_a_signer() {
+    let e = setup_env();
+    let (signers, client) = deploy_multisig(&e, 1);
+    let proposal_id = client.propose(
+        &signers.get(0).unwrap(),
+        &ProposalAction::SetSigners(signers.clone(), 1),
+    );
+    let rotation_id = client.propose(
+        &signers.get(1).unwrap(),
+        &ProposalAction::SetSigners(
+            Vec::from_array(&e, [signers.get(1).unwrap(), signers.get(2).unwrap()]),
+            1,
+        ),
+    );
+    client.execute(&signers.get(1).unwrap(), &rotation_id);
+
+    let result = client.try_cancel(&signers.get(0).unwrap(), &proposal_id);
+
+    assert!(result.is_err());
+    assert_eq!(
+        client.get_proposal(&proposal_id).status,
+        ProposalStatus::Pending
+    );
+}
+
+#[test]
+fn test_cancel_should_fail_if_caller_is_not_the_proposer() {
+    let e = setup_env();
+    let (signers, client) = deploy_multisig(&e, 2);
+    let proposal_id = client.propose(
+        &signers.get(0).unwrap(),
+        &ProposalAction::SetSigners(signers.clone(), 1),
+    );
+
+    let result = client.try_cancel(&signers.get(1).unwrap(), &proposal_id);
+    assert!(result.is_err());
+
+    client.cancel(&signers.get(0).unwrap(), &proposal_id);
+    assert_eq!(
+        client.get_proposal(&proposal_id).status,
+        ProposalStatus::Canceled
+    );
+    let result = client.try_approve(&signers.get(1).unwrap(), &proposal_id);
+    assert!(result.is_err());
+}
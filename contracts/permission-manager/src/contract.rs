@@ -1,14 +1,69 @@
-use soroban_sdk::{contract, contractimpl, Address, Env, Symbol, Vec};
+use soroban_sdk::{
+    contract, contractclient, contractimpl, contracttype, symbol_short, Address, BytesN, Env,
+    IntoVal, String, Symbol, Val, Vec,
+};
 use stellar_access::access_control::{self as access_control, AccessControl};
 use stellar_contract_utils::upgradeable::UpgradeableInternal;
 use stellar_macros::{default_impl, Upgradeable};
 
-use contracts_utils::role::{WHITELISTED_ROLE, WHITELISTER_ROLE};
+use contracts_utils::role::{ALL_ROLES, PAUSER_ROLE, WHITELISTED_ROLE, WHITELISTER_ROLE};
+
+#[contractclient(name = "WhitelistMirrorClient")]
+pub trait WhitelistMirrorInterface {
+    fn on_whitelist_update_batch(accounts: &Vec<Address>, whitelisted: &Vec<bool>);
+}
 
 #[derive(Upgradeable)]
 #[contract]
 pub struct PermissionManager;
 
+pub const MIRRORS_KEY: Symbol = symbol_short!("MIRRORS");
+pub const GLOBAL_PAUSE_KEY: Symbol = symbol_short!("GPAUSED");
+
+pub const IDENTITY_EVENT: Symbol = symbol_short!("IDENTITY");
+pub const WALLET_LINKED_EVENT: Symbol = symbol_short!("LINK");
+pub const WALLET_UNLINKED_EVENT: Symbol = symbol_short!("UNLINK");
+pub const SCOPED_ROLE_EVENT: Symbol = symbol_short!("SCOPED");
+pub const ROLE_GRANTED_EVENT: Symbol = symbol_short!("GRANTED");
+pub const ROLE_REVOKED_EVENT: Symbol = symbol_short!("REVOKED");
+pub const ROLE_BATCH_EVENT: Symbol = symbol_short!("BATCH");
+pub const GLOBAL_PAUSE_EVENT: Symbol = symbol_short!("GPAUSE");
+pub const PAUSED_EVENT: Symbol = symbol_short!("PAUSED");
+pub const UNPAUSED_EVENT: Symbol = symbol_short!("UNPAUSED");
+pub const MIRROR_EVENT: Symbol = symbol_short!("MIRROR");
+pub const MIRROR_REGISTERED_EVENT: Symbol = symbol_short!("REGISTER");
+pub const MIRROR_UNREGISTERED_EVENT: Symbol = symbol_short!("UNREGISTR");
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Identity(Address),
+    IdentityWallets(BytesN<32>),
+    ScopedRole(Address, Symbol, Address),
+    ScopedRoleMember(Address, Symbol, u32),
+    ScopedRoleMemberCount(Address, Symbol),
+    AccountScopedRole(Address, u32),
+    AccountScopedRoleIndex(Address, Symbol, Address),
+    AccountScopedRoleCount(Address),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScopedRoleGrant {
+    pub role: Symbol,
+    pub scope: Address,
+}
+
+const ONE_DAY_LEDGERS: u32 = 17_280;
+const SIXTY_DAY_LEDGERS: u32 = ONE_DAY_LEDGERS * 60;
+const MAX_WALLETS_PER_IDENTITY: u32 = 20;
+const MAX_ROLE_MEMBERS_PAGE_SIZE: u32 = 100;
+const MAX_MIRRORS: u32 = 20;
+// Each granted role writes several ledger entries, it keeps a batch within the write limits of a transaction.
+const MAX_ROLE_BATCH_SIZE: u32 = 20;
+// Each mirror writes one ledger entry per account of a WHITELISTED_ROLE batch, it bounds the writes of a batch across all the mirrors.
+const MAX_MIRROR_UPDATES_PER_BATCH: u32 = 40;
+
 #[contractimpl]
 impl PermissionManager {
     pub fn __constructor(e: &Env, admin: Address) {
@@ -16,30 +71,761 @@ impl PermissionManager {
         access_control::set_role_admin_no_auth(e, &WHITELISTED_ROLE, &WHITELISTER_ROLE);
     }
 
-    pub fn grant_role_batch(e: &Env, caller: Address, users: Vec<Address>, role: Symbol) {
-        for (index, user) in users.iter().enumerate() {
-            if index == 0 {
-                access_control::grant_role(e, &caller, &user, &role);
-            } else {
+    /// Assert that the idempotency key is not used.
+    ///
+    /// # Arguments
+    ///
+    /// * `idempotency_key` - The idempotency key. It is used to prevent duplicate calls to the same function. It is locked for 7 days.
+    ///
+    /// # Errors
+    ///
+    /// The idempotency key must not be used.
+    ///
+    fn assert_idempotency_key_not_used(e: &Env, idempotency_key: &String) {
+        let idempotency_key_already_used: bool = e
+            .storage()
+            .temporary()
+            .get(idempotency_key)
+            .unwrap_or(false);
+        assert!(
+            !idempotency_key_already_used,
+            "Idempotency key already used"
+        );
+    }
+
+    fn consume_idempotency_key(e: &Env, idempotency_key: &String) {
+        e.storage().temporary().set(idempotency_key, &true);
+        e.storage()
+            .temporary()
+            .extend_ttl(idempotency_key, ONE_DAY_LEDGERS, ONE_DAY_LEDGERS * 7);
+    }
+
+    fn auth_role_batch(e: &Env, caller: &Address, users: &Vec<Address>, role: &Symbol) {
+        caller.require_auth();
+        access_control::ensure_if_admin_or_admin_role(e, caller, role);
+        assert!(!users.is_empty(), "Empty batch");
+        assert!(users.len() <= MAX_ROLE_BATCH_SIZE, "Batch too large");
+        if *role == WHITELISTED_ROLE {
+            assert!(
+                users.len() * Self::mirrors(e).len() <= MAX_MIRROR_UPDATES_PER_BATCH,
+                "Batch too large for the mirrors"
+            );
+        }
+    }
+
+    /// Grant a role to a batch of accounts. Accounts that already have the role are skipped. It returns, for each account, whether the role was granted.
+    ///
+    /// # Arguments
+    ///
+    /// * `caller` - The address of the caller.
+    /// * `users` - The addresses of the accounts.
+    /// * `role` - The role to grant.
+    /// * `reason` - The reason of the change (e.g. the reference of the KYC case). It is emitted in the batch summary event.
+    /// * `idempotency_key` - The idempotency key. It is used to prevent duplicate calls to the same function. It is locked for 7 days.
+    ///
+    /// # Errors
+    ///
+    /// The caller must be the admin or have the admin role of the role.
+    /// The batch must not be empty.
+    /// The batch must not contain more than 20 accounts.
+    /// For the WHITELISTED_ROLE, the number of accounts times the number of mirrors must not exceed 40.
+    /// The idempotency key must not be used.
+    ///
+    pub fn grant_role_batch(
+        e: &Env,
+        caller: Address,
+        users: Vec<Address>,
+        role: Symbol,
+        reason: String,
+        idempotency_key: String,
+    ) -> Vec<bool> {
+        Self::auth_role_batch(e, &caller, &users, &role);
+        Self::assert_idempotency_key_not_used(e, &idempotency_key);
+
+        let mut outcomes = Vec::new(e);
+        let mut granted_users = Vec::new(e);
+        for user in &users {
+            let granted = access_control::has_role(e, &user, &role).is_none();
+            if granted {
                 access_control::grant_role_no_auth(e, &caller, &user, &role);
+                granted_users.push_back(user);
             }
+            outcomes.push_back(granted);
         }
+        Self::notify_mirrors(e, &granted_users, &role);
+
+        Self::consume_idempotency_key(e, &idempotency_key);
+        e.events().publish(
+            (ROLE_BATCH_EVENT, ROLE_GRANTED_EVENT),
+            (role, users, caller, reason, idempotency_key),
+        );
+        outcomes
     }
 
-    pub fn revoke_role_batch(e: &Env, caller: Address, users: Vec<Address>, role: Symbol) {
-        for (index, user) in users.iter().enumerate() {
-            if index == 0 {
-                access_control::revoke_role(e, &caller, &user, &role);
-            } else {
+    /// Revoke a role from a batch of accounts. Accounts that do not have the role are skipped. It returns, for each account, whether the role was revoked.
+    ///
+    /// # Arguments
+    ///
+    /// * `caller` - The address of the caller.
+    /// * `users` - The addresses of the accounts.
+    /// * `role` - The role to revoke.
+    /// * `reason` - The reason of the change (e.g. the reference of the KYC case). It is emitted in the batch summary event.
+    /// * `idempotency_key` - The idempotency key. It is used to prevent duplicate calls to the same function. It is locked for 7 days.
+    ///
+    /// # Errors
+    ///
+    /// The caller must be the admin or have the admin role of the role.
+    /// The batch must not be empty.
+    /// The batch must not contain more than 20 accounts.
+    /// For the WHITELISTED_ROLE, the number of accounts times the number of mirrors must not exceed 40.
+    /// The idempotency key must not be used.
+    ///
+    pub fn revoke_role_batch(
+        e: &Env,
+        caller: Address,
+        users: Vec<Address>,
+        role: Symbol,
+        reason: String,
+        idempotency_key: String,
+    ) -> Vec<bool> {
+        Self::auth_role_batch(e, &caller, &users, &role);
+        Self::assert_idempotency_key_not_used(e, &idempotency_key);
+
+        let mut outcomes = Vec::new(e);
+        let mut revoked_users = Vec::new(e);
+        for user in &users {
+            let revoked = access_control::has_role(e, &user, &role).is_some();
+            if revoked {
                 access_control::revoke_role_no_auth(e, &caller, &user, &role);
+                revoked_users.push_back(user);
             }
+            outcomes.push_back(revoked);
+        }
+        Self::notify_mirrors(e, &revoked_users, &role);
+
+        Self::consume_idempotency_key(e, &idempotency_key);
+        e.events().publish(
+            (ROLE_BATCH_EVENT, ROLE_REVOKED_EVENT),
+            (role, users, caller, reason, idempotency_key),
+        );
+        outcomes
+    }
+
+    /// Engage the circuit breaker. It halts minting, burning, transfers and redemptions of all the tokens and the execution of redemptions, while the pause of each token remains available for targeted use.
+    ///
+    /// # Arguments
+    ///
+    /// * `caller` - The address of the caller.
+    ///
+    /// # Errors
+    ///
+    /// The caller must have the PAUSER_ROLE.
+    /// The circuit breaker must not be engaged.
+    ///
+    pub fn pause_all(e: &Env, caller: Address) {
+        caller.require_auth();
+        access_control::ensure_role(e, &caller, &PAUSER_ROLE);
+        assert!(!Self::globally_paused(e), "Globally paused");
+
+        e.storage().instance().set(&GLOBAL_PAUSE_KEY, &true);
+        e.events()
+            .publish((GLOBAL_PAUSE_EVENT, PAUSED_EVENT), caller);
+    }
+
+    /// Release the circuit breaker.
+    ///
+    /// # Arguments
+    ///
+    /// * `caller` - The address of the caller.
+    ///
+    /// # Errors
+    ///
+    /// The caller must have the PAUSER_ROLE.
+    /// The circuit breaker must be engaged.
+    ///
+    pub fn unpause_all(e: &Env, caller: Address) {
+        caller.require_auth();
+        access_control::ensure_role(e, &caller, &PAUSER_ROLE);
+        assert!(Self::globally_paused(e), "Not globally paused");
+
+        e.storage().instance().remove(&GLOBAL_PAUSE_KEY);
+        e.events()
+            .publish((GLOBAL_PAUSE_EVENT, UNPAUSED_EVENT), caller);
+    }
+
+    /// Check whether the circuit breaker is engaged.
+    pub fn globally_paused(e: &Env) -> bool {
+        e.storage()
+            .instance()
+            .get(&GLOBAL_PAUSE_KEY)
+            .unwrap_or(false)
+    }
+
+    /// Register a token contract keeping a local mirror of the whitelist. It is notified of every change of the WHITELISTED_ROLE with the status of the accounts for the token.
+    ///
+    /// The mirror is called synchronously: if it fails (e.g. after a faulty upgrade), every change of the WHITELISTED_ROLE reverts until the mirror is removed with `unregister_mirror`.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The address of the token contract.
+    ///
+    /// # Errors
+    ///
+    /// The caller must be the admin.
+    /// The token must not be registered already.
+    /// There must not be more than 20 registered tokens.
+    ///
+    pub fn register_mirror(e: &Env, token: Address) {
+        access_control::enforce_admin_auth(e);
+
+        let mut mirrors = Self::mirrors(e);
+        assert!(!mirrors.contains(&token), "Mirror already registered");
+        assert!(mirrors.len() < MAX_MIRRORS, "Too many mirrors");
+        mirrors.push_back(token.clone());
+        e.storage().instance().set(&MIRRORS_KEY, &mirrors);
+
+        e.events()
+            .publish((MIRROR_EVENT, MIRROR_REGISTERED_EVENT), token);
+    }
+
+    /// Unregister a token contract keeping a local mirror of the whitelist. The token is not called, so a failing mirror can always be removed. The token then stops receiving updates and its owner must disable the mirror with `set_whitelist_mirror`.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The address of the token contract.
+    ///
+    /// # Errors
+    ///
+    /// The caller must be the admin.
+    /// The token must be registered.
+    ///
+    pub fn unregister_mirror(e: &Env, token: Address) {
+        access_control::enforce_admin_auth(e);
+
+        let mut mirrors = Self::mirrors(e);
+        let index = mirrors
+            .first_index_of(&token)
+            .expect("Mirror not registered");
+        mirrors.remove(index);
+        e.storage().instance().set(&MIRRORS_KEY, &mirrors);
+
+        e.events()
+            .publish((MIRROR_EVENT, MIRROR_UNREGISTERED_EVENT), token);
+    }
+
+    /// Get the token contracts keeping a local mirror of the whitelist.
+    pub fn mirrors(e: &Env) -> Vec<Address> {
+        e.storage()
+            .instance()
+            .get(&MIRRORS_KEY)
+            .unwrap_or(Vec::new(e))
+    }
+
+    /// Push the status of accounts to the registered mirrors when their WHITELISTED_ROLE changes, with a single call per mirror.
+    fn notify_mirrors(e: &Env, accounts: &Vec<Address>, role: &Symbol) {
+        if *role != WHITELISTED_ROLE || accounts.is_empty() {
+            return;
         }
+        for token in Self::mirrors(e) {
+            let whitelisted =
+                Self::has_scoped_roles(e, accounts.clone(), role.clone(), token.clone());
+            WhitelistMirrorClient::new(e, &token).on_whitelist_update_batch(accounts, &whitelisted);
+        }
+    }
+
+    fn notify_mirrors_of(e: &Env, account: &Address, role: &Symbol) {
+        Self::notify_mirrors(e, &Vec::from_array(e, [account.clone()]), role);
+    }
+
+    /// Grant a role to an account for a single scope (e.g. a token contract address) only.
+    ///
+    /// # Arguments
+    ///
+    /// * `caller` - The address of the caller.
+    /// * `account` - The address of the account.
+    /// * `role` - The role to grant.
+    /// * `scope` - The address the role is restricted to.
+    ///
+    /// # Errors
+    ///
+    /// The caller must be the admin or have the admin role of the role.
+    ///
+    pub fn grant_scoped_role(
+        e: &Env,
+        caller: Address,
+        account: Address,
+        role: Symbol,
+        scope: Address,
+    ) {
+        caller.require_auth();
+        access_control::ensure_if_admin_or_admin_role(e, &caller, &role);
+
+        let key = DataKey::ScopedRole(account.clone(), role.clone(), scope.clone());
+        if !e.storage().persistent().has(&key) {
+            let member_count = Self::scoped_role_member_count(e, scope.clone(), role.clone());
+            Self::set_persistent(e, &key, &member_count);
+            Self::set_persistent(
+                e,
+                &DataKey::ScopedRoleMember(scope.clone(), role.clone(), member_count),
+                &account,
+            );
+            Self::set_scoped_role_member_count(e, &scope, &role, member_count + 1);
+
+            let account_count = Self::scoped_role_count(e, account.clone());
+            Self::set_persistent(
+                e,
+                &DataKey::AccountScopedRoleIndex(account.clone(), role.clone(), scope.clone()),
+                &account_count,
+            );
+            Self::set_persistent(
+                e,
+                &DataKey::AccountScopedRole(account.clone(), account_count),
+                &ScopedRoleGrant {
+                    role: role.clone(),
+                    scope: scope.clone(),
+                },
+            );
+            Self::set_scoped_role_count(e, &account, account_count + 1);
+        }
+        Self::notify_mirrors_of(e, &account, &role);
+
+        e.events().publish(
+            (SCOPED_ROLE_EVENT, ROLE_GRANTED_EVENT),
+            (account, role, scope, caller),
+        );
+    }
+
+    /// Revoke a role granted to an account for a single scope. A role granted globally is not affected.
+    ///
+    /// # Arguments
+    ///
+    /// * `caller` - The address of the caller.
+    /// * `account` - The address of the account.
+    /// * `role` - The role to revoke.
+    /// * `scope` - The address the role is restricted to.
+    ///
+    /// # Errors
+    ///
+    /// The caller must be the admin or have the admin role of the role.
+    /// The account must hold the role for the scope.
+    ///
+    pub fn revoke_scoped_role(
+        e: &Env,
+        caller: Address,
+        account: Address,
+        role: Symbol,
+        scope: Address,
+    ) {
+        caller.require_auth();
+        access_control::ensure_if_admin_or_admin_role(e, &caller, &role);
+
+        let key = DataKey::ScopedRole(account.clone(), role.clone(), scope.clone());
+        let index: u32 = e
+            .storage()
+            .persistent()
+            .get(&key)
+            .expect("Scoped role not held");
+        let last_index = Self::scoped_role_member_count(e, scope.clone(), role.clone()) - 1;
+        if index != last_index {
+            let last_member: Address = e
+                .storage()
+                .persistent()
+                .get(&DataKey::ScopedRoleMember(
+                    scope.clone(),
+                    role.clone(),
+                    last_index,
+                ))
+                .expect("Scoped role member not found");
+            Self::set_persistent(
+                e,
+                &DataKey::ScopedRoleMember(scope.clone(), role.clone(), index),
+                &last_member,
+            );
+            Self::set_persistent(
+                e,
+                &DataKey::ScopedRole(last_member, role.clone(), scope.clone()),
+                &index,
+            );
+        }
+        e.storage().persistent().remove(&DataKey::ScopedRoleMember(
+            scope.clone(),
+            role.clone(),
+            last_index,
+        ));
+        e.storage().persistent().remove(&key);
+        Self::set_scoped_role_member_count(e, &scope, &role, last_index);
+        Self::remove_account_scoped_role(e, &account, &role, &scope);
+        Self::notify_mirrors_of(e, &account, &role);
+
+        e.events().publish(
+            (SCOPED_ROLE_EVENT, ROLE_REVOKED_EVENT),
+            (account, role, scope, caller),
+        );
+    }
+
+    /// Check whether an account holds a role for a scope, either globally or for this scope only.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The address of the account.
+    /// * `role` - The role.
+    /// * `scope` - The address the role is checked for (e.g. a token contract address).
+    pub fn has_scoped_role(e: &Env, account: Address, role: Symbol, scope: Address) -> bool {
+        access_control::has_role(e, &account, &role).is_some()
+            || e.storage()
+                .persistent()
+                .has(&DataKey::ScopedRole(account, role, scope))
+    }
+
+    /// Check whether each account of a batch holds a role globally. It saves one cross-contract call per account.
+    ///
+    /// # Arguments
+    ///
+    /// * `accounts` - The addresses of the accounts.
+    /// * `role` - The role.
+    pub fn has_roles(e: &Env, accounts: Vec<Address>, role: Symbol) -> Vec<bool> {
+        let mut has_roles = Vec::new(e);
+        for account in &accounts {
+            has_roles.push_back(access_control::has_role(e, &account, &role).is_some());
+        }
+        has_roles
+    }
+
+    /// Check whether each account of a batch holds a role for a scope, either globally or for this scope only. It saves one cross-contract call per account.
+    ///
+    /// # Arguments
+    ///
+    /// * `accounts` - The addresses of the accounts.
+    /// * `role` - The role.
+    /// * `scope` - The address the role is checked for (e.g. a token contract address).
+    pub fn has_scoped_roles(
+        e: &Env,
+        accounts: Vec<Address>,
+        role: Symbol,
+        scope: Address,
+    ) -> Vec<bool> {
+        let mut has_roles = Vec::new(e);
+        for account in &accounts {
+            has_roles.push_back(Self::has_scoped_role(
+                e,
+                account,
+                role.clone(),
+                scope.clone(),
+            ));
+        }
+        has_roles
+    }
+
+    /// Get a page of the accounts holding a role globally. Indexes are stable within a ledger but an account losing the role moves the last member into its index.
+    ///
+    /// # Arguments
+    ///
+    /// * `role` - The role.
+    /// * `cursor` - The index of the first member to return.
+    /// * `limit` - The maximum number of members to return. It is capped at 100.
+    pub fn role_members(e: &Env, role: Symbol, cursor: u32, limit: u32) -> Vec<Address> {
+        let end = cursor
+            .saturating_add(limit.min(MAX_ROLE_MEMBERS_PAGE_SIZE))
+            .min(access_control::get_role_member_count(e, &role));
+        let mut members = Vec::new(e);
+        for index in cursor..end {
+            members.push_back(access_control::get_role_member(e, &role, index));
+        }
+        members
+    }
+
+    /// Get the number of accounts holding a role for a scope only.
+    ///
+    /// # Arguments
+    ///
+    /// * `scope` - The address the role is restricted to.
+    /// * `role` - The role.
+    pub fn scoped_role_member_count(e: &Env, scope: Address, role: Symbol) -> u32 {
+        e.storage()
+            .persistent()
+            .get(&DataKey::ScopedRoleMemberCount(scope, role))
+            .unwrap_or(0)
+    }
+
+    fn set_scoped_role_member_count(e: &Env, scope: &Address, role: &Symbol, member_count: u32) {
+        let key = DataKey::ScopedRoleMemberCount(scope.clone(), role.clone());
+        if member_count == 0 {
+            e.storage().persistent().remove(&key);
+        } else {
+            Self::set_persistent(e, &key, &member_count);
+        }
+    }
+
+    /// Get a page of the accounts holding a role for a scope only, e.g. the minters of a single token. Accounts holding the role globally are listed by `role_members`. Indexes are stable within a ledger but an account losing the role moves the last member into its index.
+    ///
+    /// # Arguments
+    ///
+    /// * `scope` - The address the role is restricted to.
+    /// * `role` - The role.
+    /// * `cursor` - The index of the first member to return.
+    /// * `limit` - The maximum number of members to return. It is capped at 100.
+    pub fn scoped_role_members(
+        e: &Env,
+        scope: Address,
+        role: Symbol,
+        cursor: u32,
+        limit: u32,
+    ) -> Vec<Address> {
+        let end = cursor
+            .saturating_add(limit.min(MAX_ROLE_MEMBERS_PAGE_SIZE))
+            .min(Self::scoped_role_member_count(
+                e,
+                scope.clone(),
+                role.clone(),
+            ));
+        let mut members = Vec::new(e);
+        for index in cursor..end {
+            let member: Address = e
+                .storage()
+                .persistent()
+                .get(&DataKey::ScopedRoleMember(
+                    scope.clone(),
+                    role.clone(),
+                    index,
+                ))
+                .expect("Scoped role member not found");
+            members.push_back(member);
+        }
+        members
+    }
+
+    /// Remove a scoped role from the list of an account, moving its last scoped role into the freed index.
+    fn remove_account_scoped_role(e: &Env, account: &Address, role: &Symbol, scope: &Address) {
+        let key = DataKey::AccountScopedRoleIndex(account.clone(), role.clone(), scope.clone());
+        let index: u32 = e
+            .storage()
+            .persistent()
+            .get(&key)
+            .expect("Scoped role not held");
+        let last_index = Self::scoped_role_count(e, account.clone()) - 1;
+        if index != last_index {
+            let last_grant: ScopedRoleGrant = e
+                .storage()
+                .persistent()
+                .get(&DataKey::AccountScopedRole(account.clone(), last_index))
+                .expect("Scoped role not found");
+            Self::set_persistent(
+                e,
+                &DataKey::AccountScopedRoleIndex(
+                    account.clone(),
+                    last_grant.role.clone(),
+                    last_grant.scope.clone(),
+                ),
+                &index,
+            );
+            Self::set_persistent(
+                e,
+                &DataKey::AccountScopedRole(account.clone(), index),
+                &last_grant,
+            );
+        }
+        e.storage()
+            .persistent()
+            .remove(&DataKey::AccountScopedRole(account.clone(), last_index));
+        e.storage().persistent().remove(&key);
+        Self::set_scoped_role_count(e, account, last_index);
+    }
+
+    /// Get the number of roles held by an account for a single scope.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The address of the account.
+    pub fn scoped_role_count(e: &Env, account: Address) -> u32 {
+        e.storage()
+            .persistent()
+            .get(&DataKey::AccountScopedRoleCount(account))
+            .unwrap_or(0)
+    }
+
+    fn set_scoped_role_count(e: &Env, account: &Address, count: u32) {
+        let key = DataKey::AccountScopedRoleCount(account.clone());
+        if count == 0 {
+            e.storage().persistent().remove(&key);
+        } else {
+            Self::set_persistent(e, &key, &count);
+        }
+    }
+
+    /// Get a page of the roles held by an account for a single scope, with their scope. Roles held globally are listed by `roles_of`. Indexes are stable within a ledger but a revoked scoped role moves the last one into its index.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The address of the account.
+    /// * `cursor` - The index of the first scoped role to return.
+    /// * `limit` - The maximum number of scoped roles to return. It is capped at 100.
+    pub fn scoped_roles_of(
+        e: &Env,
+        account: Address,
+        cursor: u32,
+        limit: u32,
+    ) -> Vec<ScopedRoleGrant> {
+        let end = cursor
+            .saturating_add(limit.min(MAX_ROLE_MEMBERS_PAGE_SIZE))
+            .min(Self::scoped_role_count(e, account.clone()));
+        let mut grants = Vec::new(e);
+        for index in cursor..end {
+            let grant: ScopedRoleGrant = e
+                .storage()
+                .persistent()
+                .get(&DataKey::AccountScopedRole(account.clone(), index))
+                .expect("Scoped role not found");
+            grants.push_back(grant);
+        }
+        grants
+    }
+
+    /// Get all the roles held globally by an account. Roles held for a single scope are listed by `scoped_roles_of`.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The address of the account.
+    pub fn roles_of(e: &Env, account: Address) -> Vec<Symbol> {
+        let mut roles = Vec::new(e);
+        for role in ALL_ROLES {
+            if access_control::has_role(e, &account, &role).is_some() {
+                roles.push_back(role);
+            }
+        }
+        roles
+    }
+
+    /// Link a wallet to an investor identity. Limits that apply per legal entity are evaluated against all the wallets of an identity.
+    ///
+    /// # Arguments
+    ///
+    /// * `caller` - The address of the caller.
+    /// * `account` - The address of the wallet to link.
+    /// * `identity` - The identity ID of the legal entity owning the wallet.
+    ///
+    /// # Errors
+    ///
+    /// The caller must be the admin or have the WHITELISTER_ROLE.
+    /// The wallet must not be linked to an identity.
+    /// The identity must not have more than 20 wallets.
+    ///
+    pub fn link_wallet(e: &Env, caller: Address, account: Address, identity: BytesN<32>) {
+        caller.require_auth();
+        access_control::ensure_if_admin_or_admin_role(e, &caller, &WHITELISTED_ROLE);
+
+        let identity_key = DataKey::Identity(account.clone());
+        assert!(
+            !e.storage().persistent().has(&identity_key),
+            "Wallet already linked"
+        );
+
+        let mut wallets = Self::identity_wallets(e, identity.clone());
+        assert!(
+            wallets.len() < MAX_WALLETS_PER_IDENTITY,
+            "Too many wallets for identity"
+        );
+        wallets.push_back(account.clone());
+
+        Self::set_persistent(e, &identity_key, &identity);
+        Self::set_persistent(e, &DataKey::IdentityWallets(identity.clone()), &wallets);
+
+        e.events().publish(
+            (IDENTITY_EVENT, WALLET_LINKED_EVENT),
+            (account, identity, caller),
+        );
+    }
+
+    /// Unlink a wallet from its investor identity.
+    ///
+    /// # Arguments
+    ///
+    /// * `caller` - The address of the caller.
+    /// * `account` - The address of the wallet to unlink.
+    ///
+    /// # Errors
+    ///
+    /// The caller must be the admin or have the WHITELISTER_ROLE.
+    /// The wallet must be linked to an identity.
+    ///
+    pub fn unlink_wallet(e: &Env, caller: Address, account: Address) {
+        caller.require_auth();
+        access_control::ensure_if_admin_or_admin_role(e, &caller, &WHITELISTED_ROLE);
+
+        let identity_key = DataKey::Identity(account.clone());
+        let identity: BytesN<32> = e
+            .storage()
+            .persistent()
+            .get(&identity_key)
+            .expect("Wallet not linked");
+
+        let mut wallets = Self::identity_wallets(e, identity.clone());
+        if let Some(index) = wallets.first_index_of(&account) {
+            wallets.remove(index);
+        }
+
+        e.storage().persistent().remove(&identity_key);
+        let wallets_key = DataKey::IdentityWallets(identity.clone());
+        if wallets.is_empty() {
+            e.storage().persistent().remove(&wallets_key);
+        } else {
+            Self::set_persistent(e, &wallets_key, &wallets);
+        }
+
+        e.events().publish(
+            (IDENTITY_EVENT, WALLET_UNLINKED_EVENT),
+            (account, identity, caller),
+        );
+    }
+
+    /// Get the identity a wallet is linked to.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The address of the wallet.
+    pub fn identity_of(e: &Env, account: Address) -> Option<BytesN<32>> {
+        e.storage().persistent().get(&DataKey::Identity(account))
+    }
+
+    /// Get all the wallets linked to an identity.
+    ///
+    /// # Arguments
+    ///
+    /// * `identity` - The identity ID.
+    pub fn identity_wallets(e: &Env, identity: BytesN<32>) -> Vec<Address> {
+        e.storage()
+            .persistent()
+            .get(&DataKey::IdentityWallets(identity))
+            .unwrap_or(Vec::new(e))
+    }
+
+    /// Get the pending admin of a two-step admin transfer, if any. It must call `accept_admin_transfer` before the transfer expires.
+    pub fn pending_admin(e: &Env) -> Option<Address> {
+        e.storage()
+            .temporary()
+            .get(&access_control::AccessControlStorageKey::PendingAdmin)
+    }
+
+    fn set_persistent<V: IntoVal<Env, Val>>(e: &Env, key: &DataKey, value: &V) {
+        e.storage().persistent().set(key, value);
+        e.storage()
+            .persistent()
+            .extend_ttl(key, SIXTY_DAY_LEDGERS, SIXTY_DAY_LEDGERS);
     }
 }
 
 #[default_impl]
 #[contractimpl]
 impl AccessControl for PermissionManager {
+    fn grant_role(e: &Env, caller: Address, account: Address, role: Symbol) {
+        access_control::grant_role(e, &caller, &account, &role);
+        Self::notify_mirrors_of(e, &account, &role);
+    }
+
+    fn revoke_role(e: &Env, caller: Address, account: Address, role: Symbol) {
+        access_control::revoke_role(e, &caller, &account, &role);
+        Self::notify_mirrors_of(e, &account, &role);
+    }
+
     fn renounce_admin(_: &Env) {
         panic!("Cannot renounce admin");
     }
@@ -2,21 +2,57 @@
 
 extern crate std;
 
-use super::contract::{PermissionManager, PermissionManagerArgs, PermissionManagerClient};
-use soroban_sdk::{contract, testutils::Address as _, Address, Env, Vec};
-
-use contracts_utils::role::{MINTER_ROLE, WHITELISTED_ROLE, WHITELISTER_ROLE};
+use super::contract::{
+    PermissionManager, PermissionManagerArgs, PermissionManagerClient, ScopedRoleGrant,
+};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short,
+    testutils::{Address as _, Events},
+    Address, BytesN, Env, IntoVal, String, Symbol, Vec,
+};
+
+use contracts_utils::role::{
+    BURNER_ROLE, MINTER_ROLE, PAUSER_ROLE, WHITELISTED_ROLE, WHITELISTER_ROLE,
+};
 
 #[contract]
 struct MockContract;
 
+#[contract]
+struct MirrorMock;
+
+#[contractimpl]
+impl MirrorMock {
+    pub fn on_whitelist_update_batch(e: Env, accounts: Vec<Address>, whitelisted: Vec<bool>) {
+        let broken: bool = e
+            .storage()
+            .instance()
+            .get(&symbol_short!("BROKEN"))
+            .unwrap_or(false);
+        assert!(!broken, "Broken mirror");
+        for (account, whitelisted) in accounts.iter().zip(whitelisted.iter()) {
+            e.storage().persistent().set(&account, &whitelisted);
+        }
+    }
+
+    pub fn set_broken(e: Env, broken: bool) {
+        e.storage()
+            .instance()
+            .set(&symbol_short!("BROKEN"), &broken);
+    }
+
+    pub fn is_whitelisted(e: Env, account: Address) -> Option<bool> {
+        e.storage().persistent().get(&account)
+    }
+}
+
 fn setup_env() -> Env {
     let e: Env = Env::default();
     e.mock_all_auths();
     e
 }
 
-fn deploy_permission_manager(e: &Env) -> (Address, PermissionManagerClient) {
+fn deploy_permission_manager(e: &Env) -> (Address, PermissionManagerClient<'_>) {
     let admin: Address = Address::generate(e);
     let contract_address = e.register(
         PermissionManager,
@@ -121,7 +157,15 @@ fn test_grant_role_batch_whitelister_role_should_be_able_to_grant_whitelisted_ro
     whitelisted.push_back(whitelisted2.clone());
 
     client.grant_role(&admin, &whitelister, &WHITELISTER_ROLE);
-    client.grant_role_batch(&whitelister, &whitelisted, &WHITELISTED_ROLE);
+    let reason: String = String::from_str(&e, "KYC-1234");
+    let idempotency_key: String = String::from_str(&e, "IDEMPOTENCY_KEY");
+    client.grant_role_batch(
+        &whitelister,
+        &whitelisted,
+        &WHITELISTED_ROLE,
+        &reason,
+        &idempotency_key,
+    );
 
     let has_role_whitelisted1: Option<u32> = client.has_role(&whitelisted1, &WHITELISTED_ROLE);
     let has_role_whitelisted2: Option<u32> = client.has_role(&whitelisted2, &WHITELISTED_ROLE);
@@ -140,7 +184,15 @@ fn test_grant_role_batch_non_whitelister_role_should_not_be_able_to_grant_whitel
     whitelisted.push_back(whitelisted1.clone());
     whitelisted.push_back(whitelisted2.clone());
 
-    let result = client.try_grant_role_batch(&non_whitelister, &whitelisted, &WHITELISTED_ROLE);
+    let reason: String = String::from_str(&e, "KYC-1234");
+    let idempotency_key: String = String::from_str(&e, "IDEMPOTENCY_KEY");
+    let result = client.try_grant_role_batch(
+        &non_whitelister,
+        &whitelisted,
+        &WHITELISTED_ROLE,
+        &reason,
+        &idempotency_key,
+    );
 
     let is_failure = match result {
         Ok(Ok(_)) => false,
@@ -150,6 +202,181 @@ fn test_grant_role_batch_non_whitelister_role_should_not_be_able_to_grant_whitel
     assert!(is_failure, "Expected function to fail, but it succeeded");
 }
 
+#[test]
+fn test_grant_role_batch_should_emit_a_summary_event_with_the_reason() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+    let whitelisted = Vec::from_array(&e, [Address::generate(&e), Address::generate(&e)]);
+    let reason: String = String::from_str(&e, "KYC-1234");
+    let idempotency_key: String = String::from_str(&e, "IDEMPOTENCY_KEY");
+
+    client.grant_role_batch(
+        &admin,
+        &whitelisted,
+        &WHITELISTED_ROLE,
+        &reason,
+        &idempotency_key,
+    );
+
+    let events = e.events().all();
+    let (contract_address, topics, data) = events.last().expect("Event should be present");
+    assert_eq!(contract_address, client.address);
+    assert_eq!(
+        topics,
+        (symbol_short!("BATCH"), symbol_short!("GRANTED")).into_val(&e)
+    );
+    let (role, users, caller, event_reason, event_idempotency_key): (
+        Symbol,
+        Vec<Address>,
+        Address,
+        String,
+        String,
+    ) = data.into_val(&e);
+    assert_eq!(role, WHITELISTED_ROLE);
+    assert_eq!(users, whitelisted);
+    assert_eq!(caller, admin);
+    assert_eq!(event_reason, reason);
+    assert_eq!(event_idempotency_key, idempotency_key);
+}
+
+#[test]
+fn test_grant_role_batch_should_fail_if_idempotency_key_is_already_used() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+    let reason: String = String::from_str(&e, "KYC-1234");
+    let idempotency_key: String = String::from_str(&e, "IDEMPOTENCY_KEY");
+    client.grant_role_batch(
+        &admin,
+        &Vec::from_array(&e, [Address::generate(&e)]),
+        &WHITELISTED_ROLE,
+        &reason,
+        &idempotency_key,
+    );
+
+    let result = client.try_grant_role_batch(
+        &admin,
+        &Vec::from_array(&e, [Address::generate(&e)]),
+        &WHITELISTED_ROLE,
+        &reason,
+        &idempotency_key,
+    );
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_grant_role_batch_should_skip_accounts_that_already_have_the_role() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+    let whitelisted1: Address = Address::generate(&e);
+    let whitelisted2: Address = Address::generate(&e);
+    client.grant_role(&admin, &whitelisted1, &WHITELISTED_ROLE);
+    let reason: String = String::from_str(&e, "KYC-1234");
+    let idempotency_key: String = String::from_str(&e, "IDEMPOTENCY_KEY");
+
+    let outcomes = client.grant_role_batch(
+        &admin,
+        &Vec::from_array(&e, [whitelisted1.clone(), whitelisted2.clone()]),
+        &WHITELISTED_ROLE,
+        &reason,
+        &idempotency_key,
+    );
+
+    assert_eq!(outcomes, Vec::from_array(&e, [false, true]));
+    assert_eq!(client.has_role(&whitelisted1, &WHITELISTED_ROLE), Some(0));
+    assert_eq!(client.has_role(&whitelisted2, &WHITELISTED_ROLE), Some(1));
+}
+
+#[test]
+fn test_grant_role_batch_should_fail_if_batch_is_empty() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+    let reason: String = String::from_str(&e, "KYC-1234");
+    let idempotency_key: String = String::from_str(&e, "IDEMPOTENCY_KEY");
+
+    let result = client.try_grant_role_batch(
+        &admin,
+        &Vec::new(&e),
+        &WHITELISTED_ROLE,
+        &reason,
+        &idempotency_key,
+    );
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_grant_role_batch_should_fail_if_batch_is_too_large() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+    let mut whitelisted = Vec::new(&e);
+    for _ in 0..21 {
+        whitelisted.push_back(Address::generate(&e));
+    }
+    let reason: String = String::from_str(&e, "KYC-1234");
+    let idempotency_key: String = String::from_str(&e, "IDEMPOTENCY_KEY");
+
+    let result = client.try_grant_role_batch(
+        &admin,
+        &whitelisted,
+        &WHITELISTED_ROLE,
+        &reason,
+        &idempotency_key,
+    );
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_grant_role_batch_should_bound_the_batch_by_the_number_of_mirrors() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+    let mut mirrors = Vec::new(&e);
+    for _ in 0..3 {
+        let mirror = e.register(MirrorMock, ());
+        mirrors.push_back(mirror);
+    }
+    client.register_mirror(&mirrors.get_unchecked(0));
+    client.register_mirror(&mirrors.get_unchecked(1));
+    let mut whitelisted = Vec::new(&e);
+    for _ in 0..20 {
+        whitelisted.push_back(Address::generate(&e));
+    }
+    let reason: String = String::from_str(&e, "KYC-1234");
+
+    client.grant_role_batch(
+        &admin,
+        &whitelisted,
+        &WHITELISTED_ROLE,
+        &reason,
+        &String::from_str(&e, "IDEMPOTENCY_KEY_1"),
+    );
+
+    for mirror in mirrors.iter().take(2) {
+        let mirror_client = MirrorMockClient::new(&e, &mirror);
+        for user in &whitelisted {
+            assert_eq!(mirror_client.is_whitelisted(&user), Some(true));
+        }
+    }
+    client.register_mirror(&mirrors.get_unchecked(2));
+    let result = client.try_revoke_role_batch(
+        &admin,
+        &whitelisted,
+        &WHITELISTED_ROLE,
+        &reason,
+        &String::from_str(&e, "IDEMPOTENCY_KEY_2"),
+    );
+    assert!(result.is_err());
+    let result = client.try_revoke_role_batch(
+        &admin,
+        &whitelisted.slice(0..13),
+        &WHITELISTED_ROLE,
+        &reason,
+        &String::from_str(&e, "IDEMPOTENCY_KEY_2"),
+    );
+    assert!(result.is_ok());
+}
+
 #[test]
 fn test_revoke_role_batch_whitelister_role_should_be_able_to_revoke_whitelisted_roles() {
     let e = setup_env();
@@ -164,7 +391,15 @@ fn test_revoke_role_batch_whitelister_role_should_be_able_to_revoke_whitelisted_
     whitelisted.push_back(whitelisted1.clone());
     whitelisted.push_back(whitelisted2.clone());
 
-    client.revoke_role_batch(&whitelister, &whitelisted, &WHITELISTED_ROLE);
+    let reason: String = String::from_str(&e, "KYC-1234");
+    let idempotency_key: String = String::from_str(&e, "IDEMPOTENCY_KEY");
+    client.revoke_role_batch(
+        &whitelister,
+        &whitelisted,
+        &WHITELISTED_ROLE,
+        &reason,
+        &idempotency_key,
+    );
 
     let has_role_whitelisted1: Option<u32> = client.has_role(&whitelisted1, &WHITELISTED_ROLE);
     let has_role_whitelisted2: Option<u32> = client.has_role(&whitelisted2, &WHITELISTED_ROLE);
@@ -172,6 +407,28 @@ fn test_revoke_role_batch_whitelister_role_should_be_able_to_revoke_whitelisted_
     assert_eq!(has_role_whitelisted2, None);
 }
 
+#[test]
+fn test_revoke_role_batch_should_skip_accounts_that_do_not_have_the_role() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+    let whitelisted1: Address = Address::generate(&e);
+    let whitelisted2: Address = Address::generate(&e);
+    client.grant_role(&admin, &whitelisted2, &WHITELISTED_ROLE);
+    let reason: String = String::from_str(&e, "KYC-1234");
+    let idempotency_key: String = String::from_str(&e, "IDEMPOTENCY_KEY");
+
+    let outcomes = client.revoke_role_batch(
+        &admin,
+        &Vec::from_array(&e, [whitelisted1.clone(), whitelisted2.clone()]),
+        &WHITELISTED_ROLE,
+        &reason,
+        &idempotency_key,
+    );
+
+    assert_eq!(outcomes, Vec::from_array(&e, [false, true]));
+    assert_eq!(client.has_role(&whitelisted2, &WHITELISTED_ROLE), None);
+}
+
 #[test]
 fn test_revoke_role_batch_non_whitelister_role_should_not_be_able_to_revoke_whitelisted_role() {
     let e = setup_env();
@@ -185,7 +442,15 @@ fn test_revoke_role_batch_non_whitelister_role_should_not_be_able_to_revoke_whit
     whitelisted.push_back(whitelisted1.clone());
     whitelisted.push_back(whitelisted2.clone());
 
-    let result = client.try_revoke_role_batch(&non_whitelister, &whitelisted, &WHITELISTED_ROLE);
+    let reason: String = String::from_str(&e, "KYC-1234");
+    let idempotency_key: String = String::from_str(&e, "IDEMPOTENCY_KEY");
+    let result = client.try_revoke_role_batch(
+        &non_whitelister,
+        &whitelisted,
+        &WHITELISTED_ROLE,
+        &reason,
+        &idempotency_key,
+    );
 
     let is_failure = match result {
         Ok(Ok(_)) => false,
@@ -195,6 +460,359 @@ fn test_revoke_role_batch_non_whitelister_role_should_not_be_able_to_revoke_whit
     assert!(is_failure, "Expected function to fail, but it succeeded");
 }
 
+#[test]
+fn test_has_scoped_role_should_only_return_true_for_the_scope() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+    let user: Address = Address::generate(&e);
+    let token1: Address = Address::generate(&e);
+    let token2: Address = Address::generate(&e);
+
+    client.grant_scoped_role(&admin, &user, &MINTER_ROLE, &token1);
+
+    assert!(client.has_scoped_role(&user, &MINTER_ROLE, &token1));
+    assert!(!client.has_scoped_role(&user, &MINTER_ROLE, &token2));
+    assert_eq!(client.has_role(&user, &MINTER_ROLE), None);
+}
+
+#[test]
+fn test_has_scoped_role_should_return_true_for_any_scope_when_role_is_global() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+    let user: Address = Address::generate(&e);
+    let token: Address = Address::generate(&e);
+
+    client.grant_role(&admin, &user, &MINTER_ROLE);
+
+    assert!(client.has_scoped_role(&user, &MINTER_ROLE, &token));
+}
+
+#[test]
+fn test_revoke_scoped_role_should_revoke_the_role_for_the_scope() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+    let user: Address = Address::generate(&e);
+    let token: Address = Address::generate(&e);
+    client.grant_scoped_role(&admin, &user, &WHITELISTED_ROLE, &token);
+
+    client.revoke_scoped_role(&admin, &user, &WHITELISTED_ROLE, &token);
+
+    assert!(!client.has_scoped_role(&user, &WHITELISTED_ROLE, &token));
+    let result = client.try_revoke_scoped_role(&admin, &user, &WHITELISTED_ROLE, &token);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_grant_scoped_role_non_whitelister_role_should_not_be_able_to_grant_whitelisted_role() {
+    let e = setup_env();
+    let (_admin, client) = deploy_permission_manager(&e);
+    let caller: Address = Address::generate(&e);
+    let user: Address = Address::generate(&e);
+    let token: Address = Address::generate(&e);
+
+    let result = client.try_grant_scoped_role(&caller, &user, &WHITELISTED_ROLE, &token);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_has_scoped_roles_should_return_the_role_of_each_account() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+    let user1: Address = Address::generate(&e);
+    let user2: Address = Address::generate(&e);
+    let user3: Address = Address::generate(&e);
+    let token: Address = Address::generate(&e);
+    client.grant_role(&admin, &user1, &WHITELISTED_ROLE);
+    client.grant_scoped_role(&admin, &user3, &WHITELISTED_ROLE, &token);
+    let users = Vec::from_array(&e, [user1, user2, user3]);
+
+    assert_eq!(
+        client.has_roles(&users, &WHITELISTED_ROLE),
+        Vec::from_array(&e, [true, false, false])
+    );
+    assert_eq!(
+        client.has_scoped_roles(&users, &WHITELISTED_ROLE, &token),
+        Vec::from_array(&e, [true, false, true])
+    );
+}
+
+#[test]
+fn test_role_members_should_enumerate_role_members() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+    let minter1: Address = Address::generate(&e);
+    let minter2: Address = Address::generate(&e);
+    let minter3: Address = Address::generate(&e);
+    let users = Vec::from_array(&e, [minter1.clone(), minter2.clone(), minter3.clone()]);
+    let reason: String = String::from_str(&e, "KYC-1234");
+    let idempotency_key: String = String::from_str(&e, "IDEMPOTENCY_KEY");
+    client.grant_role_batch(&admin, &users, &MINTER_ROLE, &reason, &idempotency_key);
+
+    assert_eq!(client.get_role_member_count(&MINTER_ROLE), 3);
+    assert_eq!(client.get_role_member(&MINTER_ROLE, &1), minter2);
+    assert_eq!(
+        client.role_members(&MINTER_ROLE, &0, &2),
+        Vec::from_array(&e, [minter1, minter2])
+    );
+    assert_eq!(
+        client.role_members(&MINTER_ROLE, &2, &10),
+        Vec::from_array(&e, [minter3])
+    );
+    assert_eq!(client.role_members(&MINTER_ROLE, &3, &10), Vec::new(&e));
+    assert_eq!(client.role_members(&BURNER_ROLE, &0, &10), Vec::new(&e));
+}
+
+#[test]
+fn test_scoped_role_members_should_enumerate_members_of_the_scope_only() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+    let minter1: Address = Address::generate(&e);
+    let minter2: Address = Address::generate(&e);
+    let minter3: Address = Address::generate(&e);
+    let global_minter: Address = Address::generate(&e);
+    let token1: Address = Address::generate(&e);
+    let token2: Address = Address::generate(&e);
+    client.grant_role(&admin, &global_minter, &MINTER_ROLE);
+    client.grant_scoped_role(&admin, &minter1, &MINTER_ROLE, &token1);
+    client.grant_scoped_role(&admin, &minter2, &MINTER_ROLE, &token1);
+    client.grant_scoped_role(&admin, &minter2, &MINTER_ROLE, &token1);
+    client.grant_scoped_role(&admin, &minter3, &MINTER_ROLE, &token1);
+    client.grant_scoped_role(&admin, &minter3, &MINTER_ROLE, &token2);
+
+    assert_eq!(client.scoped_role_member_count(&token1, &MINTER_ROLE), 3);
+    assert_eq!(
+        client.scoped_role_members(&token1, &MINTER_ROLE, &0, &10),
+        Vec::from_array(&e, [minter1.clone(), minter2.clone(), minter3.clone()])
+    );
+    assert_eq!(
+        client.scoped_role_members(&token2, &MINTER_ROLE, &0, &10),
+        Vec::from_array(&e, [minter3.clone()])
+    );
+    assert_eq!(
+        client.scoped_role_members(&token1, &BURNER_ROLE, &0, &10),
+        Vec::new(&e)
+    );
+
+    client.revoke_scoped_role(&admin, &minter1, &MINTER_ROLE, &token1);
+
+    assert_eq!(
+        client.scoped_role_members(&token1, &MINTER_ROLE, &0, &10),
+        Vec::from_array(&e, [minter3.clone(), minter2.clone()])
+    );
+    client.revoke_scoped_role(&admin, &minter3, &MINTER_ROLE, &token1);
+    assert_eq!(
+        client.scoped_role_members(&token1, &MINTER_ROLE, &1, &10),
+        Vec::new(&e)
+    );
+    assert!(client.has_scoped_role(&minter2, &MINTER_ROLE, &token1));
+    assert!(!client.has_scoped_role(&minter3, &MINTER_ROLE, &token1));
+    assert!(client.has_scoped_role(&minter3, &MINTER_ROLE, &token2));
+}
+
+#[test]
+fn test_scoped_roles_of_should_enumerate_the_scoped_roles_of_an_account() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+    let user: Address = Address::generate(&e);
+    let token1: Address = Address::generate(&e);
+    let token2: Address = Address::generate(&e);
+    client.grant_role(&admin, &user, &WHITELISTED_ROLE);
+    client.grant_scoped_role(&admin, &user, &MINTER_ROLE, &token1);
+    client.grant_scoped_role(&admin, &user, &MINTER_ROLE, &token2);
+    client.grant_scoped_role(&admin, &user, &MINTER_ROLE, &token2);
+    client.grant_scoped_role(&admin, &user, &BURNER_ROLE, &token1);
+    let grant = |role: Symbol, scope: &Address| ScopedRoleGrant {
+        role,
+        scope: scope.clone(),
+    };
+
+    assert_eq!(client.scoped_role_count(&user), 3);
+    assert_eq!(
+        client.scoped_roles_of(&user, &0, &10),
+        Vec::from_array(
+            &e,
+            [
+                grant(MINTER_ROLE, &token1),
+                grant(MINTER_ROLE, &token2),
+                grant(BURNER_ROLE, &token1),
+            ]
+        )
+    );
+    assert_eq!(
+        client.scoped_roles_of(&user, &2, &10),
+        Vec::from_array(&e, [grant(BURNER_ROLE, &token1)])
+    );
+
+    client.revoke_scoped_role(&admin, &user, &MINTER_ROLE, &token1);
+
+    assert_eq!(client.scoped_role_count(&user), 2);
+    assert_eq!(
+        client.scoped_roles_of(&user, &0, &10),
+        Vec::from_array(
+            &e,
+            [grant(BURNER_ROLE, &token1), grant(MINTER_ROLE, &token2)]
+        )
+    );
+    client.revoke_scoped_role(&admin, &user, &BURNER_ROLE, &token1);
+    client.revoke_scoped_role(&admin, &user, &MINTER_ROLE, &token2);
+    assert_eq!(client.scoped_role_count(&user), 0);
+    assert_eq!(client.scoped_roles_of(&user, &0, &10), Vec::new(&e));
+    assert_eq!(
+        client.roles_of(&user),
+        Vec::from_array(&e, [WHITELISTED_ROLE])
+    );
+}
+
+#[test]
+fn test_roles_of_should_return_the_roles_of_an_account() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+    let user: Address = Address::generate(&e);
+    client.grant_role(&admin, &user, &MINTER_ROLE);
+    client.grant_role(&admin, &user, &WHITELISTED_ROLE);
+
+    let roles = client.roles_of(&user);
+
+    assert_eq!(roles, Vec::from_array(&e, [MINTER_ROLE, WHITELISTED_ROLE]));
+    assert_eq!(client.roles_of(&admin), Vec::new(&e));
+}
+
+#[test]
+fn test_pause_all_should_engage_the_circuit_breaker() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+    let pauser: Address = Address::generate(&e);
+    client.grant_role(&admin, &pauser, &PAUSER_ROLE);
+
+    client.pause_all(&pauser);
+    assert!(client.globally_paused());
+    assert!(client.try_pause_all(&pauser).is_err());
+
+    client.unpause_all(&pauser);
+    assert!(!client.globally_paused());
+    assert!(client.try_unpause_all(&pauser).is_err());
+}
+
+#[test]
+fn test_pause_all_should_fail_if_caller_is_not_pauser() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+
+    let result = client.try_pause_all(&admin);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_register_mirror_should_require_admin_auth() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+    let token: Address = Address::generate(&e);
+
+    client.register_mirror(&token);
+
+    let auths = e.auths();
+    assert_eq!(auths.len(), 1);
+    let (addr, _invocation) = &auths[0];
+    assert_eq!(addr, &admin);
+    assert_eq!(client.mirrors(), Vec::from_array(&e, [token.clone()]));
+    let result = client.try_register_mirror(&token);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_unregister_mirror_should_remove_the_mirror() {
+    let e = setup_env();
+    let (_admin, client) = deploy_permission_manager(&e);
+    let token: Address = Address::generate(&e);
+    client.register_mirror(&token);
+
+    client.unregister_mirror(&token);
+
+    assert_eq!(client.mirrors(), Vec::new(&e));
+    let result = client.try_unregister_mirror(&token);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_grant_role_batch_should_push_the_whitelist_to_each_mirror() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+    let mirror1 = e.register(MirrorMock, ());
+    let mirror2 = e.register(MirrorMock, ());
+    client.register_mirror(&mirror1);
+    client.register_mirror(&mirror2);
+    let user1: Address = Address::generate(&e);
+    let user2: Address = Address::generate(&e);
+    let users = Vec::from_array(&e, [user1.clone(), user2.clone()]);
+    let reason: String = String::from_str(&e, "KYC-1234");
+    client.grant_scoped_role(&admin, &user2, &WHITELISTED_ROLE, &mirror2);
+
+    client.grant_role_batch(
+        &admin,
+        &users,
+        &WHITELISTED_ROLE,
+        &reason,
+        &String::from_str(&e, "GRANT"),
+    );
+
+    for mirror in [&mirror1, &mirror2] {
+        let mirror_client = MirrorMockClient::new(&e, mirror);
+        assert_eq!(mirror_client.is_whitelisted(&user1), Some(true));
+        assert_eq!(mirror_client.is_whitelisted(&user2), Some(true));
+    }
+
+    client.revoke_role_batch(
+        &admin,
+        &users,
+        &WHITELISTED_ROLE,
+        &reason,
+        &String::from_str(&e, "REVOKE"),
+    );
+
+    let mirror1_client = MirrorMockClient::new(&e, &mirror1);
+    let mirror2_client = MirrorMockClient::new(&e, &mirror2);
+    assert_eq!(mirror1_client.is_whitelisted(&user1), Some(false));
+    assert_eq!(mirror1_client.is_whitelisted(&user2), Some(false));
+    assert_eq!(mirror2_client.is_whitelisted(&user1), Some(false));
+    assert_eq!(mirror2_client.is_whitelisted(&user2), Some(true));
+}
+
+#[test]
+fn test_grant_role_should_fail_while_a_failing_mirror_is_registered() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+    let mirror = e.register(MirrorMock, ());
+    let user: Address = Address::generate(&e);
+    client.register_mirror(&mirror);
+    MirrorMockClient::new(&e, &mirror).set_broken(&true);
+
+    let result = client.try_grant_role(&admin, &user, &WHITELISTED_ROLE);
+    assert!(result.is_err());
+
+    client.unregister_mirror(&mirror);
+    client.grant_role(&admin, &user, &WHITELISTED_ROLE);
+    assert_eq!(client.has_role(&user, &WHITELISTED_ROLE), Some(0));
+    client.revoke_role(&admin, &user, &WHITELISTED_ROLE);
+    assert_eq!(client.has_role(&user, &WHITELISTED_ROLE), None);
+}
+
+#[test]
+fn test_unregister_mirror_should_stop_notifying_the_mirror() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+    let mirror = e.register(MirrorMock, ());
+    let mirror_client = MirrorMockClient::new(&e, &mirror);
+    let user: Address = Address::generate(&e);
+    client.register_mirror(&mirror);
+    client.unregister_mirror(&mirror);
+
+    client.grant_role(&admin, &user, &WHITELISTED_ROLE);
+
+    assert_eq!(mirror_client.is_whitelisted(&user), None);
+}
+
 #[test]
 fn test_renounce_admin_should_panic() {
     let e = setup_env();
@@ -204,3 +822,116 @@ fn test_renounce_admin_should_panic() {
 
     assert!(result.is_err());
 }
+
+#[test]
+fn test_transfer_admin_role_should_require_acceptance_by_the_pending_admin() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+    let new_admin: Address = Address::generate(&e);
+
+    client.transfer_admin_role(&new_admin, &(e.ledger().sequence() + 100));
+
+    assert_eq!(client.get_admin(), Some(admin));
+    assert_eq!(client.pending_admin(), Some(new_admin.clone()));
+
+    client.accept_admin_transfer();
+
+    let auths = e.auths();
+    assert_eq!(auths.len(), 1);
+    let (addr, _invocation) = &auths[0];
+    assert_eq!(addr, &new_admin);
+    assert_eq!(client.get_admin(), Some(new_admin));
+    assert_eq!(client.pending_admin(), None);
+}
+
+#[test]
+fn test_transfer_admin_role_should_be_cancelled_with_a_zero_live_until_ledger() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+    let new_admin: Address = Address::generate(&e);
+    client.transfer_admin_role(&new_admin, &(e.ledger().sequence() + 100));
+
+    client.transfer_admin_role(&new_admin, &0);
+
+    assert_eq!(client.pending_admin(), None);
+    let result = client.try_accept_admin_transfer();
+    assert!(result.is_err());
+    assert_eq!(client.get_admin(), Some(admin));
+}
+
+#[test]
+fn test_link_wallet_should_link_wallets_to_identity() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+    let whitelister: Address = Address::generate(&e);
+    let wallet1: Address = Address::generate(&e);
+    let wallet2: Address = Address::generate(&e);
+    let identity: BytesN<32> = BytesN::from_array(&e, &[1; 32]);
+    client.grant_role(&admin, &whitelister, &WHITELISTER_ROLE);
+
+    client.link_wallet(&whitelister, &wallet1, &identity);
+    client.link_wallet(&whitelister, &wallet2, &identity);
+
+    assert_eq!(client.identity_of(&wallet1), Some(identity.clone()));
+    assert_eq!(client.identity_of(&wallet2), Some(identity.clone()));
+    let wallets = client.identity_wallets(&identity);
+    assert_eq!(wallets.len(), 2);
+    assert_eq!(wallets.get(0), Some(wallet1));
+    assert_eq!(wallets.get(1), Some(wallet2));
+}
+
+#[test]
+fn test_link_wallet_should_fail_if_wallet_is_already_linked() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+    let wallet: Address = Address::generate(&e);
+    let identity1: BytesN<32> = BytesN::from_array(&e, &[1; 32]);
+    let identity2: BytesN<32> = BytesN::from_array(&e, &[2; 32]);
+    client.link_wallet(&admin, &wallet, &identity1);
+
+    let result = client.try_link_wallet(&admin, &wallet, &identity2);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_link_wallet_non_whitelister_role_should_not_be_able_to_link_wallet() {
+    let e = setup_env();
+    let (_admin, client) = deploy_permission_manager(&e);
+    let non_whitelister: Address = Address::generate(&e);
+    let wallet: Address = Address::generate(&e);
+    let identity: BytesN<32> = BytesN::from_array(&e, &[1; 32]);
+
+    let result = client.try_link_wallet(&non_whitelister, &wallet, &identity);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_unlink_wallet_should_unlink_wallet_from_identity() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+    let wallet1: Address = Address::generate(&e);
+    let wallet2: Address = Address::generate(&e);
+    let identity: BytesN<32> = BytesN::from_array(&e, &[1; 32]);
+    client.link_wallet(&admin, &wallet1, &identity);
+    client.link_wallet(&admin, &wallet2, &identity);
+
+    client.unlink_wallet(&admin, &wallet1);
+
+    assert_eq!(client.identity_of(&wallet1), None);
+    let wallets = client.identity_wallets(&identity);
+    assert_eq!(wallets.len(), 1);
+    assert_eq!(wallets.get(0), Some(wallet2));
+}
+
+#[test]
+fn test_unlink_wallet_should_fail_if_wallet_is_not_linked() {
+    let e = setup_env();
+    let (admin, client) = deploy_permission_manager(&e);
+    let wallet: Address = Address::generate(&e);
+
+    let result = client.try_unlink_wallet(&admin, &wallet);
+
+    assert!(result.is_err());
+}
@@ -1,21 +1,24 @@
 use soroban_sdk::{
     contract, contractclient, contractimpl, contracttype, crypto::Hash, symbol_short, xdr::ToXdr,
-    Address, Bytes, Env, String, Symbol, Vec,
+    Address, Bytes, BytesN, Env, String, Symbol, Vec,
 };
 use stellar_access::ownable::{self as ownable, Ownable};
+use stellar_contract_utils::pausable::{self as pausable, Pausable};
 use stellar_contract_utils::upgradeable::{Upgradeable, UpgradeableInternal};
-use stellar_macros::{default_impl, only_owner, Upgradeable};
+use stellar_macros::{default_impl, only_owner, when_not_paused, Upgradeable};
 
-use contracts_utils::role::REDEMPTION_EXECUTOR_ROLE;
+use contracts_utils::role::{PAUSER_ROLE, REDEMPTION_EXECUTOR_ROLE};
 
 #[contractclient(name = "PermissionManagerClient")]
 pub trait PermissionManagerInterface {
     fn has_role(account: &Address, role: &Symbol) -> Option<u32>;
+    fn globally_paused() -> bool;
 }
 
 #[contractclient(name = "TokenClient")]
 pub trait TokenInterface {
     fn burn(account: Address, amount: i128, caller: Address);
+    fn mint(account: Address, amount: i128, caller: Address);
     fn transfer(from: Address, to: Address, amount: i128);
 }
 
@@ -24,11 +27,29 @@ pub trait TokenInterface {
 pub struct Redemption;
 
 pub const PERMISSION_MANAGER_KEY: Symbol = symbol_short!("PERM");
+pub const DRAIN_KEY: Symbol = symbol_short!("DRAIN");
 
 pub const REDEMPTION_EVENT: Symbol = symbol_short!("REDEEM");
 pub const REDEMPTION_INITIATED_EVENT: Symbol = symbol_short!("INIT");
 pub const REDEMPTION_EXECUTED_EVENT: Symbol = symbol_short!("EXEC");
 pub const REDEMPTION_CANCELLED_EVENT: Symbol = symbol_short!("CANCEL");
+pub const DRAIN_EVENT: Symbol = symbol_short!("DRAIN");
+pub const SWITCH_EVENT: Symbol = symbol_short!("SWITCH");
+pub const TOKEN_EVENT: Symbol = symbol_short!("TOKEN");
+pub const TOKEN_CONFIGURED_EVENT: Symbol = symbol_short!("CONFIG");
+pub const SWITCH_BOUNDS_EVENT: Symbol = symbol_short!("BOUNDS");
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Tokens,
+    Token(Address),
+    PendingRedemptions(Address),
+    PendingSwitches(Address),
+    SwitchRatioBounds(Address, Address),
+    SettlementDeadline(BytesN<32>),
+    Switch(BytesN<32>),
+}
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -43,10 +64,61 @@ pub enum RedemptionStatus {
     Canceled,
 }
 
+/// Lifecycle of a registered token. A winding down token accepts no new redemption but its pending redemptions can still be executed or canceled.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TokenStatus {
+    Active,
+    WindingDown,
+}
+
+/// Redemption settings of a registered token.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenConfig {
+    /// The asset paid out to redeemers on settlement, if any.
+    pub payout_asset: Option<Address>,
+    /// The minimum amount of a redemption.
+    pub min_amount: i128,
+    /// The maximum amount of a redemption, if any.
+    pub max_amount: Option<i128>,
+    /// The number of ledgers after which a pending redemption can no longer be executed, only canceled, if any.
+    pub settlement_ledgers: Option<u32>,
+    /// Whether new redemptions are accepted.
+    pub enabled: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenEntry {
+    pub status: TokenStatus,
+    pub config: TokenConfig,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ExecuteRedemptionOperation(pub Address, pub Address, pub i128, pub String);
 
+/// A switch of `amount` tokens of a share class (from token) to another (to token) requested by `from` with a `salt`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SwitchEntry(pub Address, pub Address, pub Address, pub i128, pub String);
+
+/// The bounds set by the owner on the conversion ratio of the switches from a token to another: min_numerator / min_denominator <= numerator / denominator <= max_numerator / max_denominator.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SwitchRatioBounds {
+    pub min_numerator: i128,
+    pub min_denominator: i128,
+    pub max_numerator: i128,
+    pub max_denominator: i128,
+}
+
+/// The switch to execute followed by the numerator and the denominator of the conversion ratio from the from token to the to token.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExecuteSwitchOperation(pub SwitchEntry, pub i128, pub i128);
+
 const ONE_DAY_LEDGERS: u32 = 17_280;
 const SIXTY_DAY_LEDGERS: u32 = ONE_DAY_LEDGERS * 60;
 
@@ -56,27 +128,124 @@ impl Redemption {
         ownable::set_owner(e, &owner);
     }
 
-    fn assert_has_role(e: &Env, account: &Address, role: &Symbol) {
+    fn permission_manager_client(e: &Env) -> PermissionManagerClient<'_> {
         let permission_manager: Address = e
             .storage()
             .instance()
             .get(&PERMISSION_MANAGER_KEY)
             .expect("Permission manager not set");
-        let client: PermissionManagerClient<'_> =
-            PermissionManagerClient::new(e, &permission_manager);
+        PermissionManagerClient::new(e, &permission_manager)
+    }
+
+    fn assert_has_role(e: &Env, account: &Address, role: &Symbol) {
+        let client = Self::permission_manager_client(e);
         assert!(client.has_role(account, role).is_some(), "Invalid role");
     }
 
+    fn assert_not_globally_paused(e: &Env) {
+        let client = Self::permission_manager_client(e);
+        assert!(!client.globally_paused(), "Globally paused");
+    }
+
+    fn token_entry(e: &Env, token: &Address) -> Option<TokenEntry> {
+        e.storage().persistent().get(&DataKey::Token(token.clone()))
+    }
+
+    fn set_token_entry(e: &Env, token: &Address, entry: &TokenEntry) {
+        let key = DataKey::Token(token.clone());
+        e.storage().persistent().set(&key, entry);
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, SIXTY_DAY_LEDGERS, SIXTY_DAY_LEDGERS);
+    }
+
+    fn set_tokens(e: &Env, tokens: &Vec<Address>) {
+        e.storage().persistent().set(&DataKey::Tokens, tokens);
+        e.storage()
+            .persistent()
+            .extend_ttl(&DataKey::Tokens, SIXTY_DAY_LEDGERS, SIXTY_DAY_LEDGERS);
+    }
+
+    fn set_pending_count(e: &Env, key: &DataKey, count: u32) {
+        if count == 0 {
+            e.storage().persistent().remove(key);
+        } else {
+            e.storage().persistent().set(key, &count);
+            e.storage()
+                .persistent()
+                .extend_ttl(key, SIXTY_DAY_LEDGERS, SIXTY_DAY_LEDGERS);
+        }
+    }
+
+    fn set_pending_redemptions(e: &Env, token: &Address, pending_redemptions: u32) {
+        Self::set_pending_count(
+            e,
+            &DataKey::PendingRedemptions(token.clone()),
+            pending_redemptions,
+        );
+    }
+
+    /// Record that a pending redemption of a token was executed or canceled.
+    fn settle_pending_redemption(e: &Env, token: &Address) {
+        let pending_redemptions = Self::pending_redemptions(e, token.clone())
+            .checked_sub(1)
+            .expect("No pending redemption");
+        Self::set_pending_redemptions(e, token, pending_redemptions);
+    }
+
+    /// Record a new pending switch, counted for both its from and to tokens.
+    fn add_pending_switch(e: &Env, from_token: &Address, to_token: &Address) {
+        for token in [from_token, to_token] {
+            let pending_switches = Self::pending_switches(e, token.clone());
+            Self::set_pending_count(
+                e,
+                &DataKey::PendingSwitches(token.clone()),
+                pending_switches + 1,
+            );
+        }
+    }
+
+    /// Record that a pending switch was executed or canceled.
+    fn settle_pending_switch(e: &Env, from_token: &Address, to_token: &Address) {
+        for token in [from_token, to_token] {
+            let pending_switches = Self::pending_switches(e, token.clone())
+                .checked_sub(1)
+                .expect("No pending switch");
+            Self::set_pending_count(
+                e,
+                &DataKey::PendingSwitches(token.clone()),
+                pending_switches,
+            );
+        }
+    }
+
+    fn default_token_config() -> TokenConfig {
+        TokenConfig {
+            payout_asset: None,
+            min_amount: 0,
+            max_amount: None,
+            settlement_ledgers: None,
+            enabled: true,
+        }
+    }
+
     fn assert_token_registered(e: &Env, token: &Address) {
-        let token_set: bool = e
-            .storage()
-            .instance()
-            .get(&token)
-            .expect("Caller should be token contract");
-        assert!(token_set, "Caller should be token contract");
+        Self::token_entry(e, token).expect("Caller should be token contract");
+    }
+
+    fn active_token_config(e: &Env, token: &Address) -> TokenConfig {
+        let entry = Self::token_entry(e, token).expect("Caller should be token contract");
+        assert!(entry.status == TokenStatus::Active, "Token winding down");
+        assert!(entry.config.enabled, "Token disabled");
+        e.storage().persistent().extend_ttl(
+            &DataKey::Token(token.clone()),
+            SIXTY_DAY_LEDGERS,
+            SIXTY_DAY_LEDGERS,
+        );
+        entry.config
     }
 
-    /// Add a token to the redemption contract. All token contract addresses must be added to the redemption contract.
+    /// Add a token to the redemption contract. All token contract addresses must be added to the redemption contract. A new token is enabled without limits nor settlement deadline. A winding down token is reactivated with its current configuration.
     ///
     /// # Arguments
     ///
@@ -88,10 +257,25 @@ impl Redemption {
     ///
     #[only_owner]
     pub fn add_token(e: &Env, token_contract_address: Address) {
-        e.storage().instance().set(&token_contract_address, &true);
+        let entry = match Self::token_entry(e, &token_contract_address) {
+            Some(entry) => TokenEntry {
+                status: TokenStatus::Active,
+                config: entry.config,
+            },
+            None => {
+                let mut tokens = Self::list_tokens(e);
+                tokens.push_back(token_contract_address.clone());
+                Self::set_tokens(e, &tokens);
+                TokenEntry {
+                    status: TokenStatus::Active,
+                    config: Self::default_token_config(),
+                }
+            }
+        };
+        Self::set_token_entry(e, &token_contract_address, &entry);
     }
 
-    /// Remove a token from the redemption contract.
+    /// Remove a token from the redemption contract. The token is first wound down: new redemptions and switches are rejected while pending ones can still be executed or canceled. Calling it again on a winding down token, once its pending redemptions and switches are settled, removes it.
     ///
     /// # Arguments
     ///
@@ -100,10 +284,269 @@ impl Redemption {
     /// # Errors
     ///
     /// The caller must be the owner.
+    /// The token must be registered.
+    /// A winding down token must not have pending redemptions nor pending switches, from or to it.
     ///
     #[only_owner]
     pub fn remove_token(e: &Env, token_contract_address: Address) {
-        e.storage().instance().remove(&token_contract_address);
+        let mut entry =
+            Self::token_entry(e, &token_contract_address).expect("Token not registered");
+        match entry.status {
+            TokenStatus::Active => {
+                entry.status = TokenStatus::WindingDown;
+                Self::set_token_entry(e, &token_contract_address, &entry);
+            }
+            TokenStatus::WindingDown => {
+                let pending = Self::pending_redemptions(e, token_contract_address.clone());
+                assert!(pending == 0, "Pending redemptions");
+                let pending_switches = Self::pending_switches(e, token_contract_address.clone());
+                assert!(pending_switches == 0, "Pending switches");
+                let mut tokens = Self::list_tokens(e);
+                if let Some(index) = tokens.first_index_of(&token_contract_address) {
+                    tokens.remove(index);
+                }
+                Self::set_tokens(e, &tokens);
+                e.storage()
+                    .persistent()
+                    .remove(&DataKey::Token(token_contract_address));
+            }
+        }
+    }
+
+    /// Move tokens registered by a previous version of the contract to the token registry. They were stored in the instance storage as `true`, keyed by their address. A token is moved as active with the default settings. It is called once after the upgrade, before any redemption is settled.
+    ///
+    /// The previous version did not count pending redemptions, so the count of each token is seeded from the redemptions still pending at the upgrade (initiated events without an executed or cancelled event). It must not be lower, otherwise settling the last ones fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `tokens` - The addresses of the token contracts registered by the previous version.
+    /// * `pending_redemptions` - The number of pending redemptions of each token.
+    ///
+    /// # Errors
+    ///
+    /// The caller must be the owner.
+    /// The tokens and their pending redemptions must have the same length.
+    /// The tokens must be registered in the instance storage and not in the token registry.
+    ///
+    #[only_owner]
+    pub fn migrate_registry(e: &Env, tokens: Vec<Address>, pending_redemptions: Vec<u32>) {
+        assert!(tokens.len() == pending_redemptions.len(), "Length mismatch");
+        let mut registered_tokens = Self::list_tokens(e);
+        for (token, pending_redemptions) in tokens.iter().zip(pending_redemptions.iter()) {
+            assert!(
+                Self::token_entry(e, &token).is_none(),
+                "Token already registered"
+            );
+            let registered: bool = e.storage().instance().get(&token).unwrap_or(false);
+            assert!(registered, "Token not registered");
+
+            Self::set_token_entry(
+                e,
+                &token,
+                &TokenEntry {
+                    status: TokenStatus::Active,
+                    config: Self::default_token_config(),
+                },
+            );
+            Self::set_pending_redemptions(e, &token, pending_redemptions);
+            registered_tokens.push_back(token.clone());
+            e.storage().instance().remove(&token);
+        }
+        Self::set_tokens(e, &registered_tokens);
+    }
+
+    /// Set the redemption settings of a registered token.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The address of the token contract.
+    /// * `config` - The redemption settings of the token.
+    ///
+    /// # Errors
+    ///
+    /// The caller must be the owner.
+    /// The token must be registered.
+    /// The minimum amount must not be negative nor above the maximum amount.
+    /// The payout asset must be neither the token nor the redemption contract.
+    ///
+    #[only_owner]
+    pub fn set_token_config(e: &Env, token: Address, config: TokenConfig) {
+        let mut entry = Self::token_entry(e, &token).expect("Token not registered");
+        if let Some(payout_asset) = &config.payout_asset {
+            assert!(
+                *payout_asset != token && *payout_asset != e.current_contract_address(),
+                "Invalid payout asset"
+            );
+        }
+        assert!(config.min_amount >= 0, "Invalid limits");
+        if let Some(max_amount) = config.max_amount {
+            assert!(config.min_amount <= max_amount, "Invalid limits");
+        }
+
+        entry.config = config.clone();
+        Self::set_token_entry(e, &token, &entry);
+
+        e.events()
+            .publish((TOKEN_EVENT, TOKEN_CONFIGURED_EVENT), (token, config));
+    }
+
+    /// Get the status of a token, if it is registered.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The address of the token contract.
+    pub fn token_status(e: &Env, token: Address) -> Option<TokenStatus> {
+        Self::token_entry(e, &token).map(|entry| entry.status)
+    }
+
+    /// Get the redemption settings of a token, if it is registered.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The address of the token contract.
+    pub fn token_config(e: &Env, token: Address) -> Option<TokenConfig> {
+        Self::token_entry(e, &token).map(|entry| entry.config)
+    }
+
+    /// Get the number of pending redemptions of a token.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The address of the token contract.
+    pub fn pending_redemptions(e: &Env, token: Address) -> u32 {
+        e.storage()
+            .persistent()
+            .get(&DataKey::PendingRedemptions(token))
+            .unwrap_or(0)
+    }
+
+    /// Get the number of pending switches from or to a token.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The address of the token contract.
+    pub fn pending_switches(e: &Env, token: Address) -> u32 {
+        e.storage()
+            .persistent()
+            .get(&DataKey::PendingSwitches(token))
+            .unwrap_or(0)
+    }
+
+    /// Set the bounds of the conversion ratio of the switches from a token to another. A pair of tokens without bounds cannot be switched.
+    ///
+    /// # Arguments
+    ///
+    /// * `from_token` - The address of the token switched from.
+    /// * `to_token` - The address of the token switched to.
+    /// * `bounds` - The bounds of the conversion ratio, or None to disallow the switches.
+    ///
+    /// # Errors
+    ///
+    /// The caller must be the owner.
+    /// The numerators and denominators must be positive and the minimum ratio must not be above the maximum ratio.
+    ///
+    #[only_owner]
+    pub fn set_switch_ratio_bounds(
+        e: &Env,
+        from_token: Address,
+        to_token: Address,
+        bounds: Option<SwitchRatioBounds>,
+    ) {
+        let key = DataKey::SwitchRatioBounds(from_token.clone(), to_token.clone());
+        match &bounds {
+            Some(bounds) => {
+                assert!(
+                    bounds.min_numerator > 0
+                        && bounds.min_denominator > 0
+                        && bounds.max_numerator > 0
+                        && bounds.max_denominator > 0,
+                    "Invalid bounds"
+                );
+                assert!(
+                    bounds
+                        .min_numerator
+                        .checked_mul(bounds.max_denominator)
+                        .expect("Overflow")
+                        <= bounds
+                            .max_numerator
+                            .checked_mul(bounds.min_denominator)
+                            .expect("Overflow"),
+                    "Invalid bounds"
+                );
+                e.storage().persistent().set(&key, bounds);
+                e.storage()
+                    .persistent()
+                    .extend_ttl(&key, SIXTY_DAY_LEDGERS, SIXTY_DAY_LEDGERS);
+            }
+            None => e.storage().persistent().remove(&key),
+        }
+
+        e.events().publish(
+            (SWITCH_EVENT, SWITCH_BOUNDS_EVENT),
+            (from_token, to_token, bounds),
+        );
+    }
+
+    /// Get the bounds of the conversion ratio of the switches from a token to another, if they are allowed.
+    ///
+    /// # Arguments
+    ///
+    /// * `from_token` - The address of the token switched from.
+    /// * `to_token` - The address of the token switched to.
+    pub fn switch_ratio_bounds(
+        e: &Env,
+        from_token: Address,
+        to_token: Address,
+    ) -> Option<SwitchRatioBounds> {
+        e.storage()
+            .persistent()
+            .get(&DataKey::SwitchRatioBounds(from_token, to_token))
+    }
+
+    fn assert_ratio_within_bounds(
+        e: &Env,
+        from_token: &Address,
+        to_token: &Address,
+        numerator: i128,
+        denominator: i128,
+    ) {
+        let bounds = Self::switch_ratio_bounds(e, from_token.clone(), to_token.clone())
+            .expect("Switch not allowed");
+        let above_min = numerator
+            .checked_mul(bounds.min_denominator)
+            .expect("Overflow")
+            >= bounds
+                .min_numerator
+                .checked_mul(denominator)
+                .expect("Overflow");
+        let below_max = numerator
+            .checked_mul(bounds.max_denominator)
+            .expect("Overflow")
+            <= bounds
+                .max_numerator
+                .checked_mul(denominator)
+                .expect("Overflow");
+        assert!(above_min && below_max, "Ratio out of bounds");
+    }
+
+    /// Get the registered tokens, active or winding down.
+    pub fn list_tokens(e: &Env) -> Vec<Address> {
+        e.storage()
+            .persistent()
+            .get(&DataKey::Tokens)
+            .unwrap_or(Vec::new(e))
+    }
+
+    /// Check whether a new redemption of a token would be accepted: the token is active and enabled and the contract is neither paused nor draining. Tokens check it before transferring the redeemed amount.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The address of the token contract.
+    pub fn accepts_redemptions(e: &Env, token: Address) -> bool {
+        Self::token_entry(e, &token)
+            .is_some_and(|entry| entry.status == TokenStatus::Active && entry.config.enabled)
+            && !pausable::paused(e)
+            && !Self::draining(e)
     }
 
     /// Set the permission manager (central role management authority).
@@ -123,6 +566,42 @@ impl Redemption {
             .set(&PERMISSION_MANAGER_KEY, &permission_manager);
     }
 
+    /// Get the pending owner of a two-step ownership transfer, if any. It must call `accept_ownership` before the transfer expires.
+    pub fn pending_owner(e: &Env) -> Option<Address> {
+        e.storage()
+            .temporary()
+            .get(&ownable::OwnableStorageKey::PendingOwner)
+    }
+
+    /// Enable or disable the drain mode. While draining, new redemptions are rejected but pending redemptions can still be executed or canceled.
+    ///
+    /// # Arguments
+    ///
+    /// * `caller` - The address of the caller.
+    /// * `enabled` - Whether the contract is draining.
+    ///
+    /// # Errors
+    ///
+    /// The caller must have the PAUSER_ROLE.
+    ///
+    pub fn set_draining(e: &Env, caller: Address, enabled: bool) {
+        caller.require_auth();
+        Self::assert_has_role(e, &caller, &PAUSER_ROLE);
+
+        if enabled {
+            e.storage().instance().set(&DRAIN_KEY, &true);
+        } else {
+            e.storage().instance().remove(&DRAIN_KEY);
+        }
+
+        e.events().publish((DRAIN_EVENT,), (enabled, caller));
+    }
+
+    /// Check whether the contract is draining.
+    pub fn draining(e: &Env) -> bool {
+        e.storage().instance().get(&DRAIN_KEY).unwrap_or(false)
+    }
+
     fn compute_redemption_hash(
         e: &Env,
         token: &Address,
@@ -130,10 +609,10 @@ impl Redemption {
         amount: i128,
         salt: &String,
     ) -> Hash<32> {
-        let mut redemption_entry_serialized: Bytes = token.clone().to_xdr(&e);
-        redemption_entry_serialized.append(&from.clone().to_xdr(&e));
-        redemption_entry_serialized.append(&amount.to_xdr(&e));
-        redemption_entry_serialized.append(&salt.clone().to_xdr(&e));
+        let mut redemption_entry_serialized: Bytes = token.clone().to_xdr(e);
+        redemption_entry_serialized.append(&from.clone().to_xdr(e));
+        redemption_entry_serialized.append(&amount.to_xdr(e));
+        redemption_entry_serialized.append(&salt.clone().to_xdr(e));
         e.crypto().sha256(&redemption_entry_serialized)
     }
 
@@ -156,6 +635,19 @@ impl Redemption {
             .extend_ttl(redemption_hash, SIXTY_DAY_LEDGERS, SIXTY_DAY_LEDGERS);
     }
 
+    fn assert_settlement_deadline_not_passed(e: &Env, redemption_hash: &Hash<32>) {
+        let deadline: Option<u32> = e
+            .storage()
+            .persistent()
+            .get(&DataKey::SettlementDeadline(redemption_hash.to_bytes()));
+        if let Some(deadline) = deadline {
+            assert!(
+                e.ledger().sequence() <= deadline,
+                "Settlement deadline passed"
+            );
+        }
+    }
+
     /// On redeem. It is called by a registered token contract just after the tokens are transferred to the redemption contract. The redemption is recorded as pending.
     ///
     /// # Arguments
@@ -168,16 +660,36 @@ impl Redemption {
     /// # Errors
     ///
     /// It must be called by a registered token contract.
+    /// The token must not be winding down and must be enabled.
+    /// The amount must be within the limits of the token.
+    /// The contract must not be paused nor draining.
     /// The redemption hash must be in the Null status. The redemption hash is used to prevent duplicate redemptions. All redemptions are unique.
     ///
+    #[when_not_paused]
     pub fn on_redeem(e: &Env, token: Address, from: Address, amount: i128, salt: String) {
         token.require_auth();
-        Self::assert_token_registered(e, &token);
+        let config = Self::active_token_config(e, &token);
+        assert!(!Self::draining(e), "Redemption draining");
+        assert!(amount >= config.min_amount, "Amount below minimum");
+        if let Some(max_amount) = config.max_amount {
+            assert!(amount <= max_amount, "Amount above maximum");
+        }
 
         let redemption_hash = Self::compute_redemption_hash(e, &token, &from, amount, &salt);
 
         Self::assert_redemption_status(e, &redemption_hash, RedemptionStatus::Null);
         Self::set_redemption_status(e, &redemption_hash, RedemptionStatus::Pending);
+        let pending_redemptions = Self::pending_redemptions(e, token.clone());
+        Self::set_pending_redemptions(e, &token, pending_redemptions + 1);
+        if let Some(settlement_ledgers) = config.settlement_ledgers {
+            let key = DataKey::SettlementDeadline(redemption_hash.to_bytes());
+            e.storage()
+                .persistent()
+                .set(&key, &(e.ledger().sequence() + settlement_ledgers));
+            e.storage()
+                .persistent()
+                .extend_ttl(&key, SIXTY_DAY_LEDGERS, SIXTY_DAY_LEDGERS);
+        }
 
         e.events().publish(
             (REDEMPTION_EVENT, REDEMPTION_INITIATED_EVENT),
@@ -195,10 +707,14 @@ impl Redemption {
     /// # Errors
     ///
     /// The caller must have the REDEMPTION_EXECUTOR_ROLE.
+    /// The contract must not be paused.
+    /// The permission manager must not be globally paused.
     /// The operations must not be empty.
     /// All tokens must be registered.
     /// All redemptions must be in the Pending status.
+    /// The settlement deadline of the redemptions, if any, must not be passed.
     ///
+    #[when_not_paused]
     pub fn execute_redemptions(
         e: &Env,
         caller: Address,
@@ -206,6 +722,7 @@ impl Redemption {
     ) {
         caller.require_auth();
         Self::assert_has_role(e, &caller, &REDEMPTION_EXECUTOR_ROLE);
+        Self::assert_not_globally_paused(e);
         let redemption_contract_address = e.current_contract_address();
 
         for operation in &operations {
@@ -218,6 +735,7 @@ impl Redemption {
 
             let redemption_hash = Self::compute_redemption_hash(e, &token, &from, amount, &salt);
             Self::assert_redemption_status(e, &redemption_hash, RedemptionStatus::Pending);
+            Self::assert_settlement_deadline_not_passed(e, &redemption_hash);
 
             let client: TokenClient<'_> = TokenClient::new(e, &token);
 
@@ -228,6 +746,7 @@ impl Redemption {
             );
 
             Self::set_redemption_status(e, &redemption_hash, RedemptionStatus::Executed);
+            Self::settle_pending_redemption(e, &token);
             e.events().publish(
                 (REDEMPTION_EVENT, REDEMPTION_EXECUTED_EVENT),
                 RedemptionEntry(token, from, amount, salt),
@@ -248,9 +767,12 @@ impl Redemption {
     /// # Errors
     ///
     /// The caller must have the REDEMPTION_EXECUTOR_ROLE.
+    /// The contract must not be paused.
+    /// The permission manager must not be globally paused.
     /// The token must be registered.
     /// The redemption must be in the Pending status.
     ///
+    #[when_not_paused]
     pub fn cancel_redemption(
         e: &Env,
         caller: Address,
@@ -261,6 +783,7 @@ impl Redemption {
     ) {
         caller.require_auth();
         Self::assert_has_role(e, &caller, &REDEMPTION_EXECUTOR_ROLE);
+        Self::assert_not_globally_paused(e);
         Self::assert_token_registered(e, &token);
 
         let redemption_hash = Self::compute_redemption_hash(e, &token, &from, amount, &salt);
@@ -271,11 +794,190 @@ impl Redemption {
         client.transfer(&e.current_contract_address(), &from, &amount);
 
         Self::set_redemption_status(e, &redemption_hash, RedemptionStatus::Canceled);
+        Self::settle_pending_redemption(e, &token);
         e.events().publish(
             (REDEMPTION_EVENT, REDEMPTION_CANCELLED_EVENT),
             RedemptionEntry(token, from, amount, salt),
         );
     }
+
+    fn compute_switch_hash(e: &Env, switch: &SwitchEntry) -> BytesN<32> {
+        let mut switch_entry_serialized: Bytes = switch.0.clone().to_xdr(e);
+        switch_entry_serialized.append(&switch.1.clone().to_xdr(e));
+        switch_entry_serialized.append(&switch.2.clone().to_xdr(e));
+        switch_entry_serialized.append(&switch.3.to_xdr(e));
+        switch_entry_serialized.append(&switch.4.clone().to_xdr(e));
+        e.crypto().sha256(&switch_entry_serialized).to_bytes()
+    }
+
+    fn assert_switch_status(e: &Env, switch_hash: &BytesN<32>, status: RedemptionStatus) {
+        let switch_status: RedemptionStatus = e
+            .storage()
+            .persistent()
+            .get(&DataKey::Switch(switch_hash.clone()))
+            .unwrap_or(RedemptionStatus::Null);
+        assert!(switch_status == status, "Switch not in proper status");
+    }
+
+    fn set_switch_status(e: &Env, switch_hash: &BytesN<32>, status: RedemptionStatus) {
+        let key = DataKey::Switch(switch_hash.clone());
+        e.storage().persistent().set(&key, &status);
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, SIXTY_DAY_LEDGERS, SIXTY_DAY_LEDGERS);
+    }
+
+    /// Request a switch between two share classes. The tokens of the from token are transferred to the redemption contract and the switch is recorded as pending, with the same lifecycle as a redemption.
+    ///
+    /// # Arguments
+    ///
+    /// * `switch` - The switch: from token, to token, account, amount and salt.
+    ///
+    /// # Errors
+    ///
+    /// The account must authorize the switch.
+    /// The from and to tokens must be different, registered, not winding down and enabled.
+    /// The owner must have set the ratio bounds of the switches from the from token to the to token.
+    /// The amount must be within the limits of the from token.
+    /// The contract must not be paused nor draining.
+    /// The switch hash must be in the Null status.
+    ///
+    #[when_not_paused]
+    pub fn request_switch(e: &Env, switch: SwitchEntry) {
+        let SwitchEntry(from_token, to_token, from, amount, _) = switch.clone();
+        from.require_auth();
+        assert!(from_token != to_token, "Same token");
+        let config = Self::active_token_config(e, &from_token);
+        Self::active_token_config(e, &to_token);
+        assert!(
+            Self::switch_ratio_bounds(e, from_token.clone(), to_token.clone()).is_some(),
+            "Switch not allowed"
+        );
+        assert!(!Self::draining(e), "Redemption draining");
+        assert!(amount > 0, "Amount must be positive");
+        assert!(amount >= config.min_amount, "Amount below minimum");
+        if let Some(max_amount) = config.max_amount {
+            assert!(amount <= max_amount, "Amount above maximum");
+        }
+
+        let switch_hash = Self::compute_switch_hash(e, &switch);
+        Self::assert_switch_status(e, &switch_hash, RedemptionStatus::Null);
+        Self::set_switch_status(e, &switch_hash, RedemptionStatus::Pending);
+        Self::add_pending_switch(e, &from_token, &to_token);
+
+        TokenClient::new(e, &from_token).transfer(&from, &e.current_contract_address(), &amount);
+
+        e.events()
+            .publish((SWITCH_EVENT, REDEMPTION_INITIATED_EVENT), switch);
+    }
+
+    /// Execute switches. It is called by a redemption executor. It will burn the from tokens held by the redemption contract and mint the to tokens to the account, rounded down.
+    ///
+    /// # Arguments
+    ///
+    /// * `caller` - The address of the redemption executor.
+    /// * `operations` - The switches to execute with their conversion ratios.
+    ///
+    /// # Errors
+    ///
+    /// The caller must have the REDEMPTION_EXECUTOR_ROLE.
+    /// The contract must not be paused.
+    /// The permission manager must not be globally paused.
+    /// All tokens must be registered.
+    /// All ratios must be positive, within the bounds set by the owner and give a positive amount to mint.
+    /// All switches must be in the Pending status.
+    ///
+    #[when_not_paused]
+    pub fn execute_switches(e: &Env, caller: Address, operations: Vec<ExecuteSwitchOperation>) {
+        caller.require_auth();
+        Self::assert_has_role(e, &caller, &REDEMPTION_EXECUTOR_ROLE);
+        Self::assert_not_globally_paused(e);
+        let redemption_contract_address = e.current_contract_address();
+
+        for ExecuteSwitchOperation(switch, numerator, denominator) in &operations {
+            let SwitchEntry(from_token, to_token, from, amount, _) = switch.clone();
+            Self::assert_token_registered(e, &from_token);
+            Self::assert_token_registered(e, &to_token);
+            assert!(numerator > 0 && denominator > 0, "Invalid ratio");
+            Self::assert_ratio_within_bounds(e, &from_token, &to_token, numerator, denominator);
+            let converted_amount = amount.checked_mul(numerator).expect("Overflow") / denominator;
+            assert!(converted_amount > 0, "Invalid ratio");
+
+            let switch_hash = Self::compute_switch_hash(e, &switch);
+            Self::assert_switch_status(e, &switch_hash, RedemptionStatus::Pending);
+
+            TokenClient::new(e, &from_token).burn(
+                &redemption_contract_address,
+                &amount,
+                &redemption_contract_address,
+            );
+            TokenClient::new(e, &to_token).mint(
+                &from,
+                &converted_amount,
+                &redemption_contract_address,
+            );
+
+            Self::set_switch_status(e, &switch_hash, RedemptionStatus::Executed);
+            Self::settle_pending_switch(e, &from_token, &to_token);
+            e.events().publish(
+                (SWITCH_EVENT, REDEMPTION_EXECUTED_EVENT),
+                (switch, converted_amount),
+            );
+        }
+    }
+
+    /// Cancel a switch. It is called by a redemption executor. It will transfer the from tokens back to the account.
+    ///
+    /// # Arguments
+    ///
+    /// * `caller` - The address of the redemption executor.
+    /// * `switch` - The switch to cancel.
+    ///
+    /// # Errors
+    ///
+    /// The caller must have the REDEMPTION_EXECUTOR_ROLE.
+    /// The contract must not be paused.
+    /// The permission manager must not be globally paused.
+    /// The from token must be registered.
+    /// The switch must be in the Pending status.
+    ///
+    #[when_not_paused]
+    pub fn cancel_switch(e: &Env, caller: Address, switch: SwitchEntry) {
+        caller.require_auth();
+        Self::assert_has_role(e, &caller, &REDEMPTION_EXECUTOR_ROLE);
+        Self::assert_not_globally_paused(e);
+        let SwitchEntry(from_token, to_token, from, amount, _) = switch.clone();
+        Self::assert_token_registered(e, &from_token);
+
+        let switch_hash = Self::compute_switch_hash(e, &switch);
+        Self::assert_switch_status(e, &switch_hash, RedemptionStatus::Pending);
+
+        TokenClient::new(e, &from_token).transfer(&e.current_contract_address(), &from, &amount);
+
+        Self::set_switch_status(e, &switch_hash, RedemptionStatus::Canceled);
+        Self::settle_pending_switch(e, &from_token, &to_token);
+        e.events()
+            .publish((SWITCH_EVENT, REDEMPTION_CANCELLED_EVENT), switch);
+    }
+}
+
+#[contractimpl]
+impl Pausable for Redemption {
+    fn paused(e: &Env) -> bool {
+        pausable::paused(e)
+    }
+
+    fn pause(e: &Env, caller: Address) {
+        caller.require_auth();
+        Self::assert_has_role(e, &caller, &PAUSER_ROLE);
+        pausable::pause(e);
+    }
+
+    fn unpause(e: &Env, caller: Address) {
+        caller.require_auth();
+        Self::assert_has_role(e, &caller, &PAUSER_ROLE);
+        pausable::unpause(e);
+    }
 }
 
 #[default_impl]
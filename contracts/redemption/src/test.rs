@@ -2,15 +2,20 @@
 
 extern crate std;
 
-use crate::contract::ExecuteRedemptionOperation;
+use crate::contract::{
+    ExecuteRedemptionOperation, ExecuteSwitchOperation, SwitchEntry, SwitchRatioBounds,
+};
 
-use super::contract::{Redemption, RedemptionArgs, RedemptionClient, RedemptionEntry};
-use contracts_utils::role::{REDEMPTION_EXECUTOR_ROLE, WHITELISTED_ROLE};
+use super::contract::{
+    Redemption, RedemptionArgs, RedemptionClient, RedemptionEntry, RedemptionStatus, TokenConfig,
+    TokenStatus,
+};
+use contracts_utils::role::{PAUSER_ROLE, REDEMPTION_EXECUTOR_ROLE, WHITELISTED_ROLE};
 use soroban_sdk::{
     symbol_short,
-    testutils::{Address as _, Events},
+    testutils::{Address as _, Events, Ledger},
     xdr::ToXdr,
-    Address, Env, String, Vec,
+    Address, Env, IntoVal, String, Val, Vec,
 };
 
 mod permission_manager {
@@ -29,6 +34,7 @@ mod token {
     #[contractimpl]
     impl TokenInterface for Mock {
         fn burn(_account: Address, _amount: i128, _caller: Address) {}
+        fn mint(_account: Address, _amount: i128, _caller: Address) {}
         fn transfer(_from: Address, _to: Address, _amount: i128) {}
     }
 }
@@ -40,7 +46,7 @@ fn setup_env() -> Env {
     e
 }
 
-fn deploy_redemption(e: &Env) -> (Address, Address, RedemptionClient) {
+fn deploy_redemption(e: &Env) -> (Address, Address, RedemptionClient<'_>) {
     let owner: Address = Address::generate(e);
     let redemption_address = e.register(Redemption, RedemptionArgs::__constructor(&owner.clone()));
     let client = RedemptionClient::new(e, &redemption_address);
@@ -60,7 +66,7 @@ fn deploy_permission_manager(e: &Env) -> (Address, Address, permission_manager::
 }
 
 fn deploy_token(e: &Env, token_address: &Address) {
-    e.register_at(&token_address, token::Mock, ());
+    e.register_at(token_address, token::Mock, ());
 }
 
 #[test]
@@ -73,6 +79,23 @@ fn test_should_set_owner_on_constructor() {
     assert_eq!(fetched_owner, Some(owner));
 }
 
+#[test]
+fn test_transfer_ownership_should_require_acceptance_by_the_pending_owner() {
+    let e = setup_env();
+    let (owner, _, client) = deploy_redemption(&e);
+    let new_owner: Address = Address::generate(&e);
+
+    client.transfer_ownership(&new_owner, &(e.ledger().sequence() + 100));
+
+    assert_eq!(client.get_owner(), Some(owner));
+    assert_eq!(client.pending_owner(), Some(new_owner.clone()));
+
+    client.accept_ownership();
+
+    assert_eq!(client.get_owner(), Some(new_owner));
+    assert_eq!(client.pending_owner(), None);
+}
+
 #[test]
 fn test_set_permission_manager_should_require_owner_auth() {
     let e = setup_env();
@@ -107,13 +130,340 @@ fn test_remove_token_should_require_owner_auth() {
     let e = setup_env();
     let (owner, _, client) = deploy_redemption(&e);
     let token: Address = Address::generate(&e);
+    deploy_token(&e, &token);
+    client.add_token(&token);
+
+    client.remove_token(&token);
+
+    let auths = e.auths();
+    assert_eq!(auths.len(), 1);
+    let (addr, _invocation) = &auths[0];
+    assert_eq!(addr, &owner);
+}
+
+#[test]
+fn test_remove_token_should_wind_down_then_deregister_the_token() {
+    let e = setup_env();
+    let (_, _, client) = deploy_redemption(&e);
+    let token: Address = Address::generate(&e);
+    let other_token: Address = Address::generate(&e);
+    deploy_token(&e, &token);
+    deploy_token(&e, &other_token);
+    client.add_token(&token);
+    client.add_token(&other_token);
+    assert_eq!(
+        client.list_tokens(),
+        Vec::from_array(&e, [token.clone(), other_token.clone()])
+    );
+
+    client.remove_token(&token);
+
+    assert_eq!(client.token_status(&token), Some(TokenStatus::WindingDown));
+    assert!(!client.accepts_redemptions(&token));
+    assert!(client.accepts_redemptions(&other_token));
+    assert_eq!(client.list_tokens().len(), 2);
+
+    client.remove_token(&token);
+
+    assert_eq!(client.token_status(&token), None);
+    assert_eq!(client.list_tokens(), Vec::from_array(&e, [other_token]));
+    let result = client.try_remove_token(&token);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_migrate_registry_should_move_legacy_tokens_to_the_registry() {
+    let e = setup_env();
+    let (owner, redemption_address, client) = deploy_redemption(&e);
+    let token1: Address = Address::generate(&e);
+    let token2: Address = Address::generate(&e);
+    let user: Address = Address::generate(&e);
+    deploy_token(&e, &token1);
+    deploy_token(&e, &token2);
+    e.as_contract(&redemption_address, || {
+        e.storage().instance().set(&token1, &true);
+        e.storage().instance().set(&token2, &true);
+    });
+    assert!(client
+        .try_on_redeem(&token1, &user, &100, &String::from_str(&e, "SALT1"))
+        .is_err());
+
+    client.migrate_registry(
+        &Vec::from_array(&e, [token1.clone(), token2.clone()]),
+        &Vec::from_array(&e, [0, 0]),
+    );
+
+    let auths = e.auths();
+    assert_eq!(auths.len(), 1);
+    let (addr, _invocation) = &auths[0];
+    assert_eq!(addr, &owner);
+    assert_eq!(
+        client.list_tokens(),
+        Vec::from_array(&e, [token1.clone(), token2.clone()])
+    );
+    assert_eq!(client.token_status(&token1), Some(TokenStatus::Active));
+    assert_eq!(client.token_status(&token2), Some(TokenStatus::Active));
+    e.as_contract(&redemption_address, || {
+        assert!(!e.storage().instance().has(&token1));
+        assert!(!e.storage().instance().has(&token2));
+    });
+    client.on_redeem(&token1, &user, &100, &String::from_str(&e, "SALT1"));
+    let result =
+        client.try_migrate_registry(&Vec::from_array(&e, [token1]), &Vec::from_array(&e, [0]));
+    assert!(result.is_err());
+    let unregistered_token: Address = Address::generate(&e);
+    let result = client.try_migrate_registry(
+        &Vec::from_array(&e, [unregistered_token]),
+        &Vec::from_array(&e, [0]),
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_migrate_registry_should_seed_the_pending_redemptions_of_legacy_tokens() {
+    let e = setup_env();
+    let (_, redemption_address, client) = deploy_redemption(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    let token: Address = Address::generate(&e);
+    let relayer: Address = Address::generate(&e);
+    let user: Address = Address::generate(&e);
+    let salt1: String = String::from_str(&e, "SALT1");
+    let salt2: String = String::from_str(&e, "SALT2");
+    permission_manager_client.grant_role(&admin, &relayer, &REDEMPTION_EXECUTOR_ROLE);
+    deploy_token(&e, &token);
+    e.as_contract(&redemption_address, || {
+        e.storage().instance().set(&token, &true);
+        for salt in [&salt1, &salt2] {
+            let mut redemption_entry_serialized = token.clone().to_xdr(&e);
+            redemption_entry_serialized.append(&user.clone().to_xdr(&e));
+            redemption_entry_serialized.append(&100_i128.to_xdr(&e));
+            redemption_entry_serialized.append(&salt.clone().to_xdr(&e));
+            let redemption_hash = e.crypto().sha256(&redemption_entry_serialized);
+            e.storage()
+                .persistent()
+                .set(&redemption_hash, &RedemptionStatus::Pending);
+        }
+    });
+
+    let result = client.try_migrate_registry(&Vec::from_array(&e, [token.clone()]), &Vec::new(&e));
+    assert!(result.is_err());
+    client.migrate_registry(
+        &Vec::from_array(&e, [token.clone()]),
+        &Vec::from_array(&e, [2]),
+    );
 
+    assert_eq!(client.pending_redemptions(&token), 2);
+    client.remove_token(&token);
+    assert!(client.try_remove_token(&token).is_err());
+    client.execute_redemptions(
+        &relayer,
+        &Vec::from_array(
+            &e,
+            [ExecuteRedemptionOperation(
+                token.clone(),
+                user.clone(),
+                100,
+                salt1,
+            )],
+        ),
+    );
+    client.cancel_redemption(&relayer, &token, &user, &100, &salt2);
+    assert_eq!(client.pending_redemptions(&token), 0);
     client.remove_token(&token);
+    assert_eq!(client.token_status(&token), None);
+}
+
+#[test]
+fn test_set_token_config_should_store_the_settings_of_a_registered_token() {
+    let e = setup_env();
+    let (owner, _, client) = deploy_redemption(&e);
+    let token: Address = Address::generate(&e);
+    let payout_asset: Address = Address::generate(&e);
+    let config = TokenConfig {
+        payout_asset: Some(payout_asset),
+        min_amount: 10,
+        max_amount: Some(1000),
+        settlement_ledgers: Some(100),
+        enabled: true,
+    };
+    let result = client.try_set_token_config(&token, &config);
+    assert!(result.is_err());
+    deploy_token(&e, &token);
+    client.add_token(&token);
+    let mut invalid_config = config.clone();
+    invalid_config.payout_asset = Some(token.clone());
+    assert!(client
+        .try_set_token_config(&token, &invalid_config)
+        .is_err());
+
+    client.set_token_config(&token, &config);
 
     let auths = e.auths();
     assert_eq!(auths.len(), 1);
     let (addr, _invocation) = &auths[0];
     assert_eq!(addr, &owner);
+    assert_eq!(client.token_config(&token), Some(config.clone()));
+    client.remove_token(&token);
+    client.add_token(&token);
+    assert_eq!(client.token_config(&token), Some(config));
+}
+
+#[test]
+fn test_on_redeem_should_enforce_the_limits_and_enabled_flag_of_the_token() {
+    let e = setup_env();
+    let (_, _, client) = deploy_redemption(&e);
+    let token: Address = Address::generate(&e);
+    let user: Address = Address::generate(&e);
+    let salt: String = String::from_str(&e, "SALT");
+    deploy_token(&e, &token);
+    client.add_token(&token);
+    let mut config = TokenConfig {
+        payout_asset: None,
+        min_amount: 10,
+        max_amount: Some(1000),
+        settlement_ledgers: None,
+        enabled: true,
+    };
+    client.set_token_config(&token, &config);
+
+    assert!(client.try_on_redeem(&token, &user, &9, &salt).is_err());
+    assert!(client.try_on_redeem(&token, &user, &1001, &salt).is_err());
+    config.enabled = false;
+    client.set_token_config(&token, &config);
+    assert!(!client.accepts_redemptions(&token));
+    assert!(client.try_on_redeem(&token, &user, &100, &salt).is_err());
+    config.enabled = true;
+    client.set_token_config(&token, &config);
+    assert!(client.try_on_redeem(&token, &user, &100, &salt).is_ok());
+}
+
+#[test]
+fn test_execute_redemptions_should_fail_after_the_settlement_deadline() {
+    let e = setup_env();
+    let (_, _, client) = deploy_redemption(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    let token: Address = Address::generate(&e);
+    let relayer: Address = Address::generate(&e);
+    let user: Address = Address::generate(&e);
+    let salt: String = String::from_str(&e, "SALT");
+    permission_manager_client.grant_role(&admin, &relayer, &REDEMPTION_EXECUTOR_ROLE);
+    deploy_token(&e, &token);
+    client.add_token(&token);
+    client.set_token_config(
+        &token,
+        &TokenConfig {
+            payout_asset: None,
+            min_amount: 0,
+            max_amount: None,
+            settlement_ledgers: Some(100),
+            enabled: true,
+        },
+    );
+    client.on_redeem(&token, &user, &100, &salt);
+    e.ledger().with_mut(|li| li.sequence_number += 101);
+
+    let result = client.try_execute_redemptions(
+        &relayer,
+        &Vec::from_array(
+            &e,
+            [ExecuteRedemptionOperation(
+                token.clone(),
+                user.clone(),
+                100,
+                salt.clone(),
+            )],
+        ),
+    );
+
+    assert!(result.is_err());
+    client.cancel_redemption(&relayer, &token, &user, &100, &salt);
+}
+
+#[test]
+fn test_winding_down_token_should_reject_new_redemptions_and_allow_pending_ones() {
+    let e = setup_env();
+    let (_, _, client) = deploy_redemption(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    let token: Address = Address::generate(&e);
+    let relayer: Address = Address::generate(&e);
+    let user: Address = Address::generate(&e);
+    let salt1: String = String::from_str(&e, "SALT1");
+    let salt2: String = String::from_str(&e, "SALT2");
+    let salt3: String = String::from_str(&e, "SALT3");
+    permission_manager_client.grant_role(&admin, &relayer, &REDEMPTION_EXECUTOR_ROLE);
+    deploy_token(&e, &token);
+    client.add_token(&token);
+    client.on_redeem(&token, &user, &100, &salt1);
+    client.on_redeem(&token, &user, &100, &salt2);
+
+    client.remove_token(&token);
+
+    let result = client.try_on_redeem(&token, &user, &100, &salt3);
+    assert!(result.is_err());
+    client.execute_redemptions(
+        &relayer,
+        &Vec::from_array(
+            &e,
+            [ExecuteRedemptionOperation(
+                token.clone(),
+                user.clone(),
+                100,
+                salt1,
+            )],
+        ),
+    );
+    client.cancel_redemption(&relayer, &token, &user, &100, &salt2);
+}
+
+#[test]
+fn test_remove_token_should_fail_while_redemptions_are_pending() {
+    let e = setup_env();
+    let (_, _, client) = deploy_redemption(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    let token: Address = Address::generate(&e);
+    let relayer: Address = Address::generate(&e);
+    let user: Address = Address::generate(&e);
+    let salt1: String = String::from_str(&e, "SALT1");
+    let salt2: String = String::from_str(&e, "SALT2");
+    permission_manager_client.grant_role(&admin, &relayer, &REDEMPTION_EXECUTOR_ROLE);
+    deploy_token(&e, &token);
+    client.add_token(&token);
+    client.on_redeem(&token, &user, &100, &salt1);
+    client.on_redeem(&token, &user, &100, &salt2);
+    assert_eq!(client.pending_redemptions(&token), 2);
+
+    client.remove_token(&token);
+    let result = client.try_remove_token(&token);
+    assert!(result.is_err());
+
+    client.execute_redemptions(
+        &relayer,
+        &Vec::from_array(
+            &e,
+            [ExecuteRedemptionOperation(
+                token.clone(),
+                user.clone(),
+                100,
+                salt1,
+            )],
+        ),
+    );
+    assert_eq!(client.pending_redemptions(&token), 1);
+    let result = client.try_remove_token(&token);
+    assert!(result.is_err());
+
+    client.cancel_redemption(&relayer, &token, &user, &100, &salt2);
+    assert_eq!(client.pending_redemptions(&token), 0);
+    client.remove_token(&token);
+    assert_eq!(client.token_status(&token), None);
 }
 
 #[test]
@@ -325,6 +675,42 @@ fn test_execute_redemptions_fail_if_redemption_not_initiated() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_execute_redemptions_fail_if_globally_paused() {
+    let e = setup_env();
+    let (_, _, client) = deploy_redemption(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    let token: Address = Address::generate(&e);
+    let relayer: Address = Address::generate(&e);
+    let pauser: Address = Address::generate(&e);
+    let user: Address = Address::generate(&e);
+    let salt: String = String::from_str(&e, "SALT");
+    let amount: i128 = 1000000;
+    let operations = Vec::from_array(
+        &e,
+        [ExecuteRedemptionOperation(
+            token.clone(),
+            user.clone(),
+            amount,
+            salt.clone(),
+        )],
+    );
+    permission_manager_client.grant_role(&admin, &relayer, &REDEMPTION_EXECUTOR_ROLE);
+    permission_manager_client.grant_role(&admin, &pauser, &PAUSER_ROLE);
+    deploy_token(&e, &token);
+    client.add_token(&token);
+    client.on_redeem(&token, &user, &amount, &salt);
+
+    permission_manager_client.pause_all(&pauser);
+    let result = client.try_execute_redemptions(&relayer, &operations);
+    assert!(result.is_err());
+
+    permission_manager_client.unpause_all(&pauser);
+    client.execute_redemptions(&relayer, &operations);
+}
+
 #[test]
 fn test_execute_redemptions_fail_if_not_redemption_executor() {
     let e = setup_env();
@@ -476,3 +862,373 @@ fn test_cancel_redemption_fail_if_not_redemption_executor() {
 
     assert!(result.is_err());
 }
+
+#[test]
+fn test_on_redeem_should_fail_if_paused() {
+    let e = setup_env();
+    let (_, _, client) = deploy_redemption(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    let token: Address = Address::generate(&e);
+    let pauser: Address = Address::generate(&e);
+    let user: Address = Address::generate(&e);
+    let salt: String = String::from_str(&e, "SALT");
+    permission_manager_client.grant_role(&admin, &pauser, &PAUSER_ROLE);
+    deploy_token(&e, &token);
+    client.add_token(&token);
+
+    client.pause(&pauser);
+    assert!(client.paused());
+    let result = client.try_on_redeem(&token, &user, &100, &salt);
+    assert!(result.is_err());
+
+    client.unpause(&pauser);
+    client.on_redeem(&token, &user, &100, &salt);
+}
+
+#[test]
+fn test_pause_should_fail_if_caller_is_not_pauser() {
+    let e = setup_env();
+    let (_, _, client) = deploy_redemption(&e);
+    let (_, permission_manager_address, _) = deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    let caller: Address = Address::generate(&e);
+
+    let result = client.try_pause(&caller);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_draining_should_reject_new_redemptions_and_allow_pending_ones() {
+    let e = setup_env();
+    let (_, _, client) = deploy_redemption(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    let token: Address = Address::generate(&e);
+    let pauser: Address = Address::generate(&e);
+    let relayer: Address = Address::generate(&e);
+    let user: Address = Address::generate(&e);
+    let salt1: String = String::from_str(&e, "SALT1");
+    let salt2: String = String::from_str(&e, "SALT2");
+    let salt3: String = String::from_str(&e, "SALT3");
+    permission_manager_client.grant_role(&admin, &pauser, &PAUSER_ROLE);
+    permission_manager_client.grant_role(&admin, &relayer, &REDEMPTION_EXECUTOR_ROLE);
+    deploy_token(&e, &token);
+    client.add_token(&token);
+    client.on_redeem(&token, &user, &100, &salt1);
+    client.on_redeem(&token, &user, &100, &salt2);
+
+    client.set_draining(&pauser, &true);
+
+    assert!(client.draining());
+    let result = client.try_on_redeem(&token, &user, &100, &salt3);
+    assert!(result.is_err());
+    client.execute_redemptions(
+        &relayer,
+        &Vec::from_array(
+            &e,
+            [ExecuteRedemptionOperation(
+                token.clone(),
+                user.clone(),
+                100,
+                salt1,
+            )],
+        ),
+    );
+    client.cancel_redemption(&relayer, &token, &user, &100, &salt2);
+}
+
+#[test]
+fn test_set_draining_should_fail_if_caller_is_not_pauser() {
+    let e = setup_env();
+    let (_, _, client) = deploy_redemption(&e);
+    let (_, permission_manager_address, _) = deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    let caller: Address = Address::generate(&e);
+
+    let result = client.try_set_draining(&caller, &true);
+
+    assert!(result.is_err());
+}
+
+fn deploy_switch_tokens(e: &Env, client: &RedemptionClient<'_>) -> (Address, Address) {
+    let from_token: Address = Address::generate(e);
+    let to_token: Address = Address::generate(e);
+    deploy_token(e, &from_token);
+    deploy_token(e, &to_token);
+    client.add_token(&from_token);
+    client.add_token(&to_token);
+    client.set_switch_ratio_bounds(&from_token, &to_token, &Some(switch_ratio_bounds()));
+
+    (from_token, to_token)
+}
+
+fn switch_ratio_bounds() -> SwitchRatioBounds {
+    SwitchRatioBounds {
+        min_numerator: 1,
+        min_denominator: 2,
+        max_numerator: 2,
+        max_denominator: 1,
+    }
+}
+
+#[test]
+fn test_set_switch_ratio_bounds_should_require_owner_auth_and_valid_bounds() {
+    let e = setup_env();
+    let (owner, _, client) = deploy_redemption(&e);
+    let from_token: Address = Address::generate(&e);
+    let to_token: Address = Address::generate(&e);
+
+    client.set_switch_ratio_bounds(&from_token, &to_token, &Some(switch_ratio_bounds()));
+
+    let auths = e.auths();
+    assert_eq!(auths.len(), 1);
+    let (addr, _invocation) = &auths[0];
+    assert_eq!(addr, &owner);
+    assert_eq!(
+        client.switch_ratio_bounds(&from_token, &to_token),
+        Some(switch_ratio_bounds())
+    );
+    assert_eq!(client.switch_ratio_bounds(&to_token, &from_token), None);
+    let mut bounds = switch_ratio_bounds();
+    bounds.min_numerator = 5;
+    assert!(client
+        .try_set_switch_ratio_bounds(&from_token, &to_token, &Some(bounds.clone()))
+        .is_err());
+    bounds.min_numerator = 1;
+    bounds.max_denominator = 0;
+    assert!(client
+        .try_set_switch_ratio_bounds(&from_token, &to_token, &Some(bounds))
+        .is_err());
+    client.set_switch_ratio_bounds(&from_token, &to_token, &None);
+    assert_eq!(client.switch_ratio_bounds(&from_token, &to_token), None);
+}
+
+#[test]
+fn test_request_switch_should_require_account_auth_and_emit_a_switch_initiated_event() {
+    let e = setup_env();
+    let (_, redemption_address, client) = deploy_redemption(&e);
+    let (from_token, to_token) = deploy_switch_tokens(&e, &client);
+    let user: Address = Address::generate(&e);
+    let switch = SwitchEntry(
+        from_token,
+        to_token,
+        user.clone(),
+        100,
+        String::from_str(&e, "SALT"),
+    );
+
+    client.request_switch(&switch);
+
+    let auths = e.auths();
+    assert_eq!(auths.len(), 1);
+    let (addr, _invocation) = &auths[0];
+    assert_eq!(addr, &user);
+    let events = e.events().all();
+    let switch_event = Vec::get(&events, Vec::len(&events) - 1).expect("Event should be present");
+    assert_eq!(switch_event.0, redemption_address);
+    let topics: Vec<Val> = (symbol_short!("SWITCH"), symbol_short!("INIT")).into_val(&e);
+    assert_eq!(switch_event.1, topics);
+    assert_eq!(switch_event.2.to_xdr(&e), switch.clone().to_xdr(&e));
+    let result = client.try_request_switch(&switch);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_request_switch_should_fail_if_tokens_are_the_same_or_not_registered() {
+    let e = setup_env();
+    let (_, _, client) = deploy_redemption(&e);
+    let (from_token, _) = deploy_switch_tokens(&e, &client);
+    let unregistered_token: Address = Address::generate(&e);
+    let user: Address = Address::generate(&e);
+    let salt: String = String::from_str(&e, "SALT");
+
+    let result = client.try_request_switch(&SwitchEntry(
+        from_token.clone(),
+        from_token.clone(),
+        user.clone(),
+        100,
+        salt.clone(),
+    ));
+    assert!(result.is_err());
+    let result = client.try_request_switch(&SwitchEntry(
+        from_token,
+        unregistered_token,
+        user,
+        100,
+        salt,
+    ));
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_request_switch_should_fail_if_the_switch_is_not_allowed() {
+    let e = setup_env();
+    let (_, _, client) = deploy_redemption(&e);
+    let (from_token, to_token) = deploy_switch_tokens(&e, &client);
+    let user: Address = Address::generate(&e);
+
+    let result = client.try_request_switch(&SwitchEntry(
+        to_token,
+        from_token,
+        user,
+        100,
+        String::from_str(&e, "SALT"),
+    ));
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_execute_switches_should_emit_the_converted_amount() {
+    let e = setup_env();
+    let (_, redemption_address, client) = deploy_redemption(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    let (from_token, to_token) = deploy_switch_tokens(&e, &client);
+    let relayer: Address = Address::generate(&e);
+    let user: Address = Address::generate(&e);
+    permission_manager_client.grant_role(&admin, &relayer, &REDEMPTION_EXECUTOR_ROLE);
+    let switch = SwitchEntry(
+        from_token,
+        to_token,
+        user,
+        100,
+        String::from_str(&e, "SALT"),
+    );
+    client.request_switch(&switch);
+    let operations = Vec::from_array(&e, [ExecuteSwitchOperation(switch.clone(), 108, 100)]);
+
+    client.execute_switches(&relayer, &operations);
+
+    let events = e.events().all();
+    let switch_event = Vec::get(&events, Vec::len(&events) - 1).expect("Event should be present");
+    assert_eq!(switch_event.0, redemption_address);
+    let topics: Vec<Val> = (symbol_short!("SWITCH"), symbol_short!("EXEC")).into_val(&e);
+    assert_eq!(switch_event.1, topics);
+    assert_eq!(switch_event.2.to_xdr(&e), (switch, 108_i128).to_xdr(&e));
+    let result = client.try_execute_switches(&relayer, &operations);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_execute_switches_should_fail_if_not_redemption_executor_or_ratio_is_invalid() {
+    let e = setup_env();
+    let (_, _, client) = deploy_redemption(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    let (from_token, to_token) = deploy_switch_tokens(&e, &client);
+    let relayer: Address = Address::generate(&e);
+    let stranger: Address = Address::generate(&e);
+    let user: Address = Address::generate(&e);
+    permission_manager_client.grant_role(&admin, &relayer, &REDEMPTION_EXECUTOR_ROLE);
+    let switch = SwitchEntry(
+        from_token,
+        to_token,
+        user,
+        100,
+        String::from_str(&e, "SALT"),
+    );
+    client.request_switch(&switch);
+
+    let result = client.try_execute_switches(
+        &stranger,
+        &Vec::from_array(&e, [ExecuteSwitchOperation(switch.clone(), 1, 1)]),
+    );
+    assert!(result.is_err());
+    let result = client.try_execute_switches(
+        &relayer,
+        &Vec::from_array(&e, [ExecuteSwitchOperation(switch.clone(), 1, 0)]),
+    );
+    assert!(result.is_err());
+    let result = client.try_execute_switches(
+        &relayer,
+        &Vec::from_array(&e, [ExecuteSwitchOperation(switch.clone(), 1, 1000)]),
+    );
+    assert!(result.is_err());
+    let result = client.try_execute_switches(
+        &relayer,
+        &Vec::from_array(&e, [ExecuteSwitchOperation(switch.clone(), 201, 100)]),
+    );
+    assert!(result.is_err());
+    let result = client.try_execute_switches(
+        &relayer,
+        &Vec::from_array(&e, [ExecuteSwitchOperation(switch.clone(), 49, 100)]),
+    );
+    assert!(result.is_err());
+    client.execute_switches(
+        &relayer,
+        &Vec::from_array(&e, [ExecuteSwitchOperation(switch, 2, 1)]),
+    );
+}
+
+#[test]
+fn test_cancel_switch_should_prevent_execution() {
+    let e = setup_env();
+    let (_, _, client) = deploy_redemption(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    let (from_token, to_token) = deploy_switch_tokens(&e, &client);
+    let relayer: Address = Address::generate(&e);
+    let user: Address = Address::generate(&e);
+    permission_manager_client.grant_role(&admin, &relayer, &REDEMPTION_EXECUTOR_ROLE);
+    let switch = SwitchEntry(
+        from_token,
+        to_token,
+        user,
+        100,
+        String::from_str(&e, "SALT"),
+    );
+    client.request_switch(&switch);
+
+    client.cancel_switch(&relayer, &switch);
+
+    let result = client.try_execute_switches(
+        &relayer,
+        &Vec::from_array(&e, [ExecuteSwitchOperation(switch.clone(), 1, 1)]),
+    );
+    assert!(result.is_err());
+    let result = client.try_cancel_switch(&relayer, &switch);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_remove_token_should_fail_while_switches_are_pending() {
+    let e = setup_env();
+    let (_, _, client) = deploy_redemption(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    let (from_token, to_token) = deploy_switch_tokens(&e, &client);
+    let relayer: Address = Address::generate(&e);
+    let user: Address = Address::generate(&e);
+    permission_manager_client.grant_role(&admin, &relayer, &REDEMPTION_EXECUTOR_ROLE);
+    let switch = SwitchEntry(
+        from_token.clone(),
+        to_token.clone(),
+        user,
+        100,
+        String::from_str(&e, "SALT"),
+    );
+    client.request_switch(&switch);
+    assert_eq!(client.pending_switches(&from_token), 1);
+    assert_eq!(client.pending_switches(&to_token), 1);
+
+    client.remove_token(&from_token);
+    client.remove_token(&to_token);
+
+    assert!(client.try_remove_token(&from_token).is_err());
+    assert!(client.try_remove_token(&to_token).is_err());
+    client.cancel_switch(&relayer, &switch);
+    assert_eq!(client.pending_switches(&from_token), 0);
+    assert_eq!(client.pending_switches(&to_token), 0);
+    client.remove_token(&from_token);
+    client.remove_token(&to_token);
+    assert_eq!(client.list_tokens(), Vec::new(&e));
+}
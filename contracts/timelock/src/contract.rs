@@ -0,0 +1,235 @@
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short, Address, Env, Symbol, Val, Vec,
+};
+use stellar_access::ownable::{self as ownable, Ownable};
+use stellar_macros::{default_impl, only_owner};
+
+#[contract]
+pub struct Timelock;
+
+pub const GUARDIAN_KEY: Symbol = symbol_short!("GUARDIAN");
+pub const DELAY_KEY: Symbol = symbol_short!("DELAY");
+pub const OPERATION_COUNT_KEY: Symbol = symbol_short!("COUNT");
+
+pub const TIMELOCK_EVENT: Symbol = symbol_short!("TIMELOCK");
+pub const OPERATION_SCHEDULED_EVENT: Symbol = symbol_short!("SCHEDULE");
+pub const OPERATION_EXECUTED_EVENT: Symbol = symbol_short!("EXECUTE");
+pub const OPERATION_CANCELED_EVENT: Symbol = symbol_short!("CANCEL");
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Operation(u32),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TimelockAction {
+    /// Invoke a function of a contract (e.g. `set_permission_manager`, `set_redemption`, `upgrade`) with the given arguments.
+    Invoke(Address, Symbol, Vec<Val>),
+    /// Set the delay, in ledgers, between the scheduling and the execution of an operation.
+    SetDelay(u32),
+    /// Set the guardian allowed to cancel scheduled operations.
+    SetGuardian(Address),
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OperationStatus {
+    Scheduled,
+    Executed,
+    Canceled,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Operation {
+    pub action: TimelockAction,
+    pub eta: u32,
+    pub status: OperationStatus,
+}
+
+const ONE_DAY_LEDGERS: u32 = 17_280;
+const SIXTY_DAY_LEDGERS: u32 = ONE_DAY_LEDGERS * 60;
+const MIN_DELAY: u32 = ONE_DAY_LEDGERS;
+const GRACE_PERIOD: u32 = ONE_DAY_LEDGERS * 14;
+
+#[contractimpl]
+impl Timelock {
+    /// The delay, in ledgers, must be at least one day.
+    pub fn __constructor(e: &Env, owner: Address, guardian: Address, delay: u32) {
+        ownable::set_owner(e, &owner);
+        e.storage().instance().set(&GUARDIAN_KEY, &guardian);
+        Self::set_delay(e, delay);
+    }
+
+    fn set_delay(e: &Env, delay: u32) {
+        assert!(delay >= MIN_DELAY, "Delay too short");
+        e.storage().instance().set(&DELAY_KEY, &delay);
+    }
+
+    fn set_operation(e: &Env, operation_id: u32, operation: &Operation) {
+        let key = DataKey::Operation(operation_id);
+        e.storage().persistent().set(&key, operation);
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, SIXTY_DAY_LEDGERS, SIXTY_DAY_LEDGERS);
+    }
+
+    fn scheduled_operation(e: &Env, operation_id: u32) -> Operation {
+        let operation = Self::get_operation(e, operation_id);
+        assert!(
+            operation.status == OperationStatus::Scheduled,
+            "Operation not scheduled"
+        );
+        operation
+    }
+
+    /// Get the delay, in ledgers, between the scheduling and the execution of an operation.
+    pub fn delay(e: &Env) -> u32 {
+        e.storage()
+            .instance()
+            .get(&DELAY_KEY)
+            .expect("Delay not set")
+    }
+
+    /// Get the guardian allowed to cancel scheduled operations.
+    pub fn guardian(e: &Env) -> Address {
+        e.storage()
+            .instance()
+            .get(&GUARDIAN_KEY)
+            .expect("Guardian not set")
+    }
+
+    /// Get the number of operations scheduled so far.
+    pub fn operation_count(e: &Env) -> u32 {
+        e.storage()
+            .instance()
+            .get(&OPERATION_COUNT_KEY)
+            .unwrap_or(0)
+    }
+
+    /// Get an operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `operation_id` - The operation ID.
+    pub fn get_operation(e: &Env, operation_id: u32) -> Operation {
+        e.storage()
+            .persistent()
+            .get(&DataKey::Operation(operation_id))
+            .expect("Operation not found")
+    }
+
+    /// Schedule an operation. It can be executed once the current delay has elapsed, and for 14 days after that.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - The action to execute.
+    ///
+    /// # Errors
+    ///
+    /// The caller must be the owner.
+    /// A new delay must be at least one day.
+    ///
+    #[only_owner]
+    pub fn schedule(e: &Env, action: TimelockAction) -> u32 {
+        if let TimelockAction::SetDelay(delay) = action {
+            assert!(delay >= MIN_DELAY, "Delay too short");
+        }
+        let operation_id = Self::operation_count(e);
+        e.storage()
+            .instance()
+            .set(&OPERATION_COUNT_KEY, &(operation_id + 1));
+
+        let operation = Operation {
+            action,
+            eta: e.ledger().sequence() + Self::delay(e),
+            status: OperationStatus::Scheduled,
+        };
+        Self::set_operation(e, operation_id, &operation);
+
+        e.events().publish(
+            (TIMELOCK_EVENT, OPERATION_SCHEDULED_EVENT),
+            (operation_id, operation),
+        );
+        operation_id
+    }
+
+    /// Execute a scheduled operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `operation_id` - The operation ID.
+    ///
+    /// # Errors
+    ///
+    /// The caller must be the owner.
+    /// The operation must be scheduled.
+    /// The eta of the operation must be reached.
+    /// The grace period of 14 days after the eta must not be over.
+    ///
+    #[only_owner]
+    pub fn execute(e: &Env, operation_id: u32) {
+        let mut operation = Self::scheduled_operation(e, operation_id);
+        assert!(
+            e.ledger().sequence() >= operation.eta,
+            "Operation not ready"
+        );
+        assert!(
+            e.ledger().sequence() <= operation.eta + GRACE_PERIOD,
+            "Operation expired"
+        );
+
+        operation.status = OperationStatus::Executed;
+        Self::set_operation(e, operation_id, &operation);
+
+        match operation.action {
+            TimelockAction::Invoke(contract, function, args) => {
+                e.invoke_contract::<Val>(&contract, &function, args);
+            }
+            TimelockAction::SetDelay(delay) => {
+                Self::set_delay(e, delay);
+            }
+            TimelockAction::SetGuardian(guardian) => {
+                e.storage().instance().set(&GUARDIAN_KEY, &guardian);
+            }
+        }
+
+        e.events()
+            .publish((TIMELOCK_EVENT, OPERATION_EXECUTED_EVENT), operation_id);
+    }
+
+    /// Cancel a scheduled operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `caller` - The address of the guardian or of the owner.
+    /// * `operation_id` - The operation ID.
+    ///
+    /// # Errors
+    ///
+    /// The caller must be the guardian or the owner.
+    /// The operation must be scheduled.
+    ///
+    pub fn cancel(e: &Env, caller: Address, operation_id: u32) {
+        caller.require_auth();
+        assert!(
+            caller == Self::guardian(e) || Some(caller.clone()) == ownable::get_owner(e),
+            "Not the guardian"
+        );
+
+        let mut operation = Self::scheduled_operation(e, operation_id);
+        operation.status = OperationStatus::Canceled;
+        Self::set_operation(e, operation_id, &operation);
+
+        e.events().publish(
+            (TIMELOCK_EVENT, OPERATION_CANCELED_EVENT),
+            (operation_id, caller),
+        );
+    }
+}
+
+#[default_impl]
+#[contractimpl]
+impl Ownable for Timelock {}
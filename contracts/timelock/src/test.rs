@@ -0,0 +1,223 @@
+#![cfg(test)]
+
+extern crate std;
+
+use super::contract::{OperationStatus, Timelock, TimelockAction, TimelockClient};
+use contracts_utils::role::MINTER_ROLE;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env, IntoVal, Symbol,
+};
+
+mod permission_manager {
+    use soroban_sdk::contractimport;
+
+    contractimport!(file = "../../wasm/permission_manager.wasm");
+}
+
+const DELAY: u32 = 17_280;
+const GRACE_PERIOD: u32 = 14 * 17_280;
+
+fn setup_env() -> Env {
+    let e: Env = Env::default();
+    e.mock_all_auths();
+    e.ledger()
+        .with_mut(|li| li.min_persistent_entry_ttl = 60 * 17_280);
+    e
+}
+
+fn deploy_timelock(e: &Env) -> (Address, Address, TimelockClient<'_>) {
+    let owner: Address = Address::generate(e);
+    let guardian: Address = Address::generate(e);
+    let timelock_address = e.register(Timelock, (owner.clone(), guardian.clone(), DELAY));
+    let client = TimelockClient::new(e, &timelock_address);
+
+    (owner, guardian, client)
+}
+
+fn deploy_permission_manager<'a>(e: &Env, admin: &Address) -> permission_manager::Client<'a> {
+    let permission_manager_address = e.register(
+        permission_manager::WASM,
+        permission_manager::Args::__constructor(admin),
+    );
+    permission_manager::Client::new(e, &permission_manager_address)
+}
+
+fn grant_minter_action(
+    e: &Env,
+    timelock: &TimelockClient<'_>,
+    permission_manager: &permission_manager::Client<'_>,
+    account: &Address,
+) -> TimelockAction {
+    TimelockAction::Invoke(
+        permission_manager.address.clone(),
+        Symbol::new(e, "grant_role"),
+        (timelock.address.clone(), account.clone(), MINTER_ROLE).into_val(e),
+    )
+}
+
+fn advance_ledger(e: &Env, ledgers: u32) {
+    e.ledger().with_mut(|li| li.sequence_number += ledgers);
+}
+
+#[test]
+fn test_should_set_owner_guardian_and_delay_on_constructor() {
+    let e = setup_env();
+    let (owner, guardian, client) = deploy_timelock(&e);
+
+    assert_eq!(client.get_owner(), Some(owner));
+    assert_eq!(client.guardian(), guardian);
+    assert_eq!(client.delay(), DELAY);
+}
+
+#[test]
+#[should_panic]
+fn test_constructor_should_panic_if_delay_is_too_short() {
+    let e = setup_env();
+    let owner: Address = Address::generate(&e);
+    let guardian: Address = Address::generate(&e);
+
+    e.register(Timelock, (owner, guardian, 0_u32));
+}
+
+#[test]
+fn test_schedule_should_fail_if_new_delay_is_too_short() {
+    let e = setup_env();
+    let (_, _, client) = deploy_timelock(&e);
+
+    let result = client.try_schedule(&TimelockAction::SetDelay(DELAY - 1));
+
+    assert!(result.is_err());
+    assert_eq!(client.operation_count(), 0);
+}
+
+#[test]
+fn test_schedule_should_record_the_operation_with_an_eta() {
+    let e = setup_env();
+    let (owner, _, client) = deploy_timelock(&e);
+    let action = TimelockAction::SetDelay(2 * DELAY);
+
+    let operation_id = client.schedule(&action);
+
+    let auths = e.auths();
+    assert_eq!(auths.len(), 1);
+    let (addr, _invocation) = &auths[0];
+    assert_eq!(addr, &owner);
+    let operation = client.get_operation(&operation_id);
+    assert_eq!(operation.action, action);
+    assert_eq!(operation.eta, e.ledger().sequence() + DELAY);
+    assert_eq!(operation.status, OperationStatus::Scheduled);
+    assert_eq!(client.operation_count(), 1);
+}
+
+#[test]
+fn test_execute_should_fail_before_eta() {
+    let e = setup_env();
+    let (_, _, client) = deploy_timelock(&e);
+    let permission_manager = deploy_permission_manager(&e, &client.address);
+    let account: Address = Address::generate(&e);
+    let operation_id = client.schedule(&grant_minter_action(
+        &e,
+        &client,
+        &permission_manager,
+        &account,
+    ));
+    advance_ledger(&e, DELAY - 1);
+
+    let result = client.try_execute(&operation_id);
+
+    assert!(result.is_err());
+    assert!(permission_manager
+        .has_role(&account, &MINTER_ROLE)
+        .is_none());
+}
+
+#[test]
+fn test_execute_should_invoke_the_target_contract_after_eta() {
+    let e = setup_env();
+    let (_, _, client) = deploy_timelock(&e);
+    let permission_manager = deploy_permission_manager(&e, &client.address);
+    let account: Address = Address::generate(&e);
+    let operation_id = client.schedule(&grant_minter_action(
+        &e,
+        &client,
+        &permission_manager,
+        &account,
+    ));
+    advance_ledger(&e, DELAY);
+
+    client.execute(&operation_id);
+
+    assert!(permission_manager
+        .has_role(&account, &MINTER_ROLE)
+        .is_some());
+    assert_eq!(
+        client.get_operation(&operation_id).status,
+        OperationStatus::Executed
+    );
+    let result = client.try_execute(&operation_id);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_execute_should_fail_after_the_grace_period() {
+    let e = setup_env();
+    let (_, _, client) = deploy_timelock(&e);
+    let operation_id = client.schedule(&TimelockAction::SetDelay(2 * DELAY));
+    advance_ledger(&e, DELAY + GRACE_PERIOD + 1);
+
+    let result = client.try_execute(&operation_id);
+
+    assert!(result.is_err());
+    assert_eq!(client.delay(), DELAY);
+    assert_eq!(
+        client.get_operation(&operation_id).status,
+        OperationStatus::Scheduled
+    );
+}
+
+#[test]
+fn test_execute_should_update_delay_and_guardian() {
+    let e = setup_env();
+    let (_, _, client) = deploy_timelock(&e);
+    let new_guardian: Address = Address::generate(&e);
+    let delay_id = client.schedule(&TimelockAction::SetDelay(2 * DELAY));
+    let guardian_id = client.schedule(&TimelockAction::SetGuardian(new_guardian.clone()));
+    advance_ledger(&e, DELAY);
+
+    client.execute(&delay_id);
+    client.execute(&guardian_id);
+
+    assert_eq!(client.delay(), 2 * DELAY);
+    assert_eq!(client.guardian(), new_guardian);
+}
+
+#[test]
+fn test_cancel_should_prevent_execution() {
+    let e = setup_env();
+    let (_, guardian, client) = deploy_timelock(&e);
+    let operation_id = client.schedule(&TimelockAction::SetDelay(2 * DELAY));
+
+    client.cancel(&guardian, &operation_id);
+    advance_ledger(&e, DELAY);
+
+    assert_eq!(
+        client.get_operation(&operation_id).status,
+        OperationStatus::Canceled
+    );
+    let result = client.try_execute(&operation_id);
+    assert!(result.is_err());
+    assert_eq!(client.delay(), DELAY);
+}
+
+#[test]
+fn test_cancel_should_fail_if_caller_is_not_the_guardian() {
+    let e = setup_env();
+    let (_, _, client) = deploy_timelock(&e);
+    let stranger: Address = Address::generate(&e);
+    let operation_id = client.schedule(&TimelockAction::SetDelay(2 * DELAY));
+
+    let result = client.try_cancel(&stranger, &operation_id);
+
+    assert!(result.is_err());
+}
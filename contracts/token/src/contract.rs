@@ -1,24 +1,36 @@
 use soroban_sdk::{
-    contract, contractclient, contractimpl, contracttype, symbol_short, Address, Env, String,
-    Symbol, Vec,
+    contract, contractclient, contractimpl, contracttype, symbol_short, Address, BytesN, Env,
+    IntoVal, String, Symbol, Val, Vec,
 };
 use stellar_access::ownable::{self as ownable, Ownable};
 use stellar_contract_utils::pausable::{self as pausable, Pausable};
 use stellar_contract_utils::upgradeable::UpgradeableInternal;
 use stellar_macros::{default_impl, only_owner, when_not_paused, Upgradeable};
 use stellar_tokens::fungible::burnable::emit_burn;
-use stellar_tokens::fungible::Base;
+use stellar_tokens::fungible::{
+    emit_mint, emit_transfer, Base, StorageKey, BALANCE_EXTEND_AMOUNT, BALANCE_TTL_THRESHOLD,
+};
 
-use contracts_utils::role::{BURNER_ROLE, MINTER_ROLE, PAUSER_ROLE, WHITELISTED_ROLE};
+use contracts_utils::role::{
+    BURNER_ROLE, DOC_MANAGER_ROLE, MINTER_ROLE, PAUSER_ROLE, SNAPSHOT_ROLE, WHITELISTED_ROLE,
+};
 
 #[contractclient(name = "PermissionManagerClient")]
 pub trait PermissionManagerInterface {
-    fn has_role(account: &Address, role: &Symbol) -> Option<u32>;
+    fn globally_paused() -> bool;
+    fn mirrors() -> Vec<Address>;
+    fn has_scoped_role(account: &Address, role: &Symbol, scope: &Address) -> bool;
+    fn has_scoped_roles(accounts: &Vec<Address>, role: &Symbol, scope: &Address) -> Vec<bool>;
+    fn identity_of(account: &Address) -> Option<BytesN<32>>;
+    fn identity_wallets(identity: &BytesN<32>) -> Vec<Address>;
 }
 
 #[contractclient(name = "RedemptionClient")]
 pub trait RedemptionInterface {
     fn on_redeem(e: &Env, token: Address, from: Address, amount: i128, salt: String);
+    fn accepts_redemptions(e: &Env, token: Address) -> bool;
+    fn pending_redemptions(e: &Env, token: Address) -> u32;
+    fn pending_switches(e: &Env, token: Address) -> u32;
 }
 
 #[derive(Upgradeable)]
@@ -27,6 +39,113 @@ pub struct Token;
 
 pub const PERMISSION_MANAGER_KEY: Symbol = symbol_short!("PERM");
 pub const REDEMPTION_KEY: Symbol = symbol_short!("REDEMP");
+pub const HOLDER_COUNT_KEY: Symbol = symbol_short!("HOLDERS");
+pub const HOLDER_SUPPLY_KEY: Symbol = symbol_short!("HOLDSUPPL");
+pub const INVESTOR_COUNT_KEY: Symbol = symbol_short!("INVESTORS");
+pub const MAX_HOLDERS_KEY: Symbol = symbol_short!("MAXHOLD");
+pub const SNAPSHOT_ID_KEY: Symbol = symbol_short!("SNAPID");
+pub const WHITELIST_MIRROR_KEY: Symbol = symbol_short!("WLMIRROR");
+pub const SPLIT_EPOCH_KEY: Symbol = symbol_short!("SPLIT");
+pub const SPLIT_CARRY_KEY: Symbol = symbol_short!("SPLITCARY");
+pub const SPLIT_PENDING_KEY: Symbol = symbol_short!("SPLITPEND");
+pub const FUND_INFO_KEY: Symbol = symbol_short!("FUNDINFO");
+
+pub const SNAPSHOT_EVENT: Symbol = symbol_short!("SNAPSHOT");
+pub const OPERATION_PAUSED_EVENT: Symbol = symbol_short!("OPPAUSED");
+pub const OPERATION_UNPAUSED_EVENT: Symbol = symbol_short!("OPUNPAUSE");
+pub const SPLIT_EVENT: Symbol = symbol_short!("SPLIT");
+pub const SPLIT_REMAINDER_EVENT: Symbol = symbol_short!("REMAINDER");
+pub const METADATA_EVENT: Symbol = symbol_short!("METADATA");
+pub const FUND_INFO_EVENT: Symbol = symbol_short!("FUNDINFO");
+pub const DOCUMENT_EVENT: Symbol = symbol_short!("DOCUMENT");
+pub const DOCUMENT_UPDATED_EVENT: Symbol = symbol_short!("UPDATED");
+pub const DOCUMENT_REMOVED_EVENT: Symbol = symbol_short!("REMOVED");
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Holder(u32),
+    HolderIndex(Address),
+    HolderInvestor(Address),
+    IdentityHolders(BytesN<32>),
+    ProtocolAccount(Address),
+    Checkpoint(CheckpointSeries, u32),
+    CheckpointCount(CheckpointSeries),
+    Whitelisted(Address),
+    OperationPaused(TokenOperation),
+    Split(u32),
+    SplitEpoch(Address),
+    Document(Symbol),
+    Documents,
+}
+
+/// Investor a holder is counted as against the maximum number of holders: the identity its wallet is linked to in the permission manager, or the account itself.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Investor {
+    Account(Address),
+    Identity(BytesN<32>),
+}
+
+/// Operations that can be paused independently.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TokenOperation {
+    Mint,
+    Burn,
+    Transfer,
+    Redeem,
+}
+
+const TOKEN_OPERATIONS: [TokenOperation; 4] = [
+    TokenOperation::Mint,
+    TokenOperation::Burn,
+    TokenOperation::Transfer,
+    TokenOperation::Redeem,
+];
+
+/// Values checkpointed for snapshots: the balance of an account or the total supply. Each checkpoint of a series is stored in its own entry, indexed from zero, so that the cost of a change does not grow with the number of snapshots.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CheckpointSeries {
+    Balance(Address),
+    TotalSupply,
+}
+
+/// Value of a balance or of the total supply recorded the first time it changes after a snapshot.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Checkpoint(pub u32, pub i128);
+
+/// Ratio applied to every balance by a split, and the last snapshot taken before it. Values of snapshots up to it are in pre-split units.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SplitRecord {
+    pub numerator: i128,
+    pub denominator: i128,
+    pub snapshot_id: u32,
+}
+
+/// Extended metadata of the fund share class represented by the token.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FundInfo {
+    pub isin: String,
+    pub fund_lei: String,
+    pub share_class_currency: String,
+    pub prospectus_uri: String,
+    pub prospectus_hash: BytesN<32>,
+    pub kiid_hash: BytesN<32>,
+}
+
+/// Document attached to the token (prospectus, KIID, ...) with the ledger timestamp of its last update.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Document {
+    pub uri: String,
+    pub hash: BytesN<32>,
+    pub updated_at: u64,
+}
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -37,6 +156,8 @@ pub struct MintBatchOperation(pub Address, pub i128);
 pub struct BurnBatchOperation(pub Address, pub i128);
 
 const ONE_DAY_LEDGERS: u32 = 17_280;
+const MAX_HOLDERS_PAGE_SIZE: u32 = 100;
+const MAX_DOCUMENTS: u32 = 20;
 
 #[contractimpl]
 impl Token {
@@ -45,15 +166,74 @@ impl Token {
         ownable::set_owner(e, &owner);
     }
 
-    fn assert_has_role(e: &Env, account: &Address, role: &Symbol) {
+    fn permission_manager_client(e: &Env) -> PermissionManagerClient<'_> {
         let permission_manager: Address = e
             .storage()
             .instance()
             .get(&PERMISSION_MANAGER_KEY)
             .expect("Permission manager not set");
-        let client: PermissionManagerClient<'_> =
-            PermissionManagerClient::new(e, &permission_manager);
-        assert!(client.has_role(account, &role).is_some(), "Invalid role");
+        PermissionManagerClient::new(e, &permission_manager)
+    }
+
+    pub(crate) fn assert_has_role(e: &Env, account: &Address, role: &Symbol) {
+        if Self::uses_whitelist_mirror(e, role) {
+            assert!(Self::is_whitelisted(e, account.clone()), "Invalid role");
+            return;
+        }
+        let client = Self::permission_manager_client(e);
+        assert!(
+            client.has_scoped_role(account, role, &e.current_contract_address()),
+            "Invalid role"
+        );
+    }
+
+    /// Check the role of all the accounts with a single call to the permission manager.
+    pub(crate) fn assert_all_have_role(e: &Env, accounts: &Vec<Address>, role: &Symbol) {
+        if Self::uses_whitelist_mirror(e, role) {
+            for account in accounts {
+                assert!(Self::is_whitelisted(e, account), "Invalid role");
+            }
+            return;
+        }
+        let client = Self::permission_manager_client(e);
+        let has_roles = client.has_scoped_roles(accounts, role, &e.current_contract_address());
+        assert!(has_roles.iter().all(|has_role| has_role), "Invalid role");
+    }
+
+    /// Assert that the operation is not paused on the token and that the permission manager circuit breaker, which halts all the tokens at once, is not engaged. The circuit breaker is always read from the permission manager, including when the whitelist mirror is used.
+    fn assert_operation_not_paused(e: &Env, operation: TokenOperation) {
+        assert!(
+            !e.storage()
+                .instance()
+                .has(&DataKey::OperationPaused(operation)),
+            "Operation paused"
+        );
+        assert!(
+            !Self::permission_manager_client(e).globally_paused(),
+            "Globally paused"
+        );
+    }
+
+    fn assert_caller_is_permission_manager(e: &Env) {
+        let permission_manager: Address = e
+            .storage()
+            .instance()
+            .get(&PERMISSION_MANAGER_KEY)
+            .expect("Permission manager not set");
+        permission_manager.require_auth();
+    }
+
+    fn uses_whitelist_mirror(e: &Env, role: &Symbol) -> bool {
+        *role == WHITELISTED_ROLE && Self::whitelist_mirror(e)
+    }
+
+    fn set_whitelisted(e: &Env, account: &Address, whitelisted: bool) {
+        let key = DataKey::Whitelisted(account.clone());
+        if whitelisted {
+            Self::set_persistent(e, &key, &true);
+        } else {
+            e.storage().persistent().remove(&key);
+        }
     }
 
     /// Set the permission manager (central role management authority).
@@ -69,6 +249,105 @@ impl Token {
             .set(&PERMISSION_MANAGER_KEY, &permission_manager);
     }
 
+    /// Set the name and the symbol of the token. The decimals cannot be changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the token.
+    /// * `symbol` - The symbol of the token.
+    ///
+    #[only_owner]
+    pub fn set_metadata(e: &Env, name: String, symbol: String) {
+        Base::set_metadata(e, Base::decimals(e), name.clone(), symbol.clone());
+
+        e.events().publish((METADATA_EVENT,), (name, symbol));
+    }
+
+    /// Set the extended metadata of the fund share class.
+    ///
+    /// # Arguments
+    ///
+    /// * `fund_info` - The ISIN, fund LEI, share class currency, prospectus URI and hash and KIID hash.
+    ///
+    #[only_owner]
+    pub fn set_fund_info(e: &Env, fund_info: FundInfo) {
+        e.storage().instance().set(&FUND_INFO_KEY, &fund_info);
+
+        e.events().publish((FUND_INFO_EVENT,), fund_info);
+    }
+
+    fn auth_document_manager(e: &Env, caller: &Address) {
+        caller.require_auth();
+        if ownable::get_owner(e).as_ref() != Some(caller) {
+            Self::assert_has_role(e, caller, &DOC_MANAGER_ROLE);
+        }
+    }
+
+    /// Add or update a document attached to the token.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the document.
+    /// * `uri` - The URI of the document.
+    /// * `hash` - The hash of the content of the document.
+    /// * `caller` - The address of the caller.
+    ///
+    /// # Errors
+    ///
+    /// The caller must be the owner or have the DOC_MANAGER_ROLE.
+    /// There must not be more than 20 documents.
+    ///
+    pub fn set_document(e: &Env, name: Symbol, uri: String, hash: BytesN<32>, caller: Address) {
+        Self::auth_document_manager(e, &caller);
+
+        let mut names = Self::all_documents(e);
+        if !names.contains(&name) {
+            assert!(names.len() < MAX_DOCUMENTS, "Too many documents");
+            names.push_back(name.clone());
+            Self::set_persistent(e, &DataKey::Documents, &names);
+        }
+        let document = Document {
+            uri: uri.clone(),
+            hash: hash.clone(),
+            updated_at: e.ledger().timestamp(),
+        };
+        Self::set_persistent(e, &DataKey::Document(name.clone()), &document);
+
+        e.events()
+            .publish((DOCUMENT_EVENT, DOCUMENT_UPDATED_EVENT), (name, uri, hash));
+    }
+
+    /// Remove a document attached to the token.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the document.
+    /// * `caller` - The address of the caller.
+    ///
+    /// # Errors
+    ///
+    /// The caller must be the owner or have the DOC_MANAGER_ROLE.
+    /// The document must exist.
+    ///
+    pub fn remove_document(e: &Env, name: Symbol, caller: Address) {
+        Self::auth_document_manager(e, &caller);
+
+        let document = Self::get_document(e, name.clone()).expect("Document not found");
+        let mut names = Self::all_documents(e);
+        if let Some(index) = names.first_index_of(&name) {
+            names.remove(index);
+        }
+        Self::set_persistent(e, &DataKey::Documents, &names);
+        e.storage()
+            .persistent()
+            .remove(&DataKey::Document(name.clone()));
+
+        e.events().publish(
+            (DOCUMENT_EVENT, DOCUMENT_REMOVED_EVENT),
+            (name, document.uri, document.hash),
+        );
+    }
+
     /// Set the redemption (redemption contract).
     ///
     /// # Arguments
@@ -77,7 +356,661 @@ impl Token {
     ///
     #[only_owner]
     pub fn set_redemption(e: &Env, redemption: Address) {
+        let previous_redemption: Option<Address> = e.storage().instance().get(&REDEMPTION_KEY);
         e.storage().instance().set(&REDEMPTION_KEY, &redemption);
+
+        if let Some(previous_redemption) = previous_redemption {
+            Self::sync_investor(e, &previous_redemption, true);
+        }
+        Self::sync_investor(e, &redemption, true);
+    }
+
+    /// Flag an account as a protocol contract (distribution, migration, ...). Protocol contracts, like the redemption contract, are not counted against the maximum number of holders.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The address of the account.
+    /// * `protocol` - Whether the account is a protocol contract.
+    ///
+    /// # Errors
+    ///
+    /// The caller must be the owner.
+    ///
+    #[only_owner]
+    pub fn set_protocol_account(e: &Env, account: Address, protocol: bool) {
+        let key = DataKey::ProtocolAccount(account.clone());
+        if protocol {
+            Self::set_persistent(e, &key, &true);
+        } else {
+            e.storage().persistent().remove(&key);
+        }
+        Self::sync_investor(e, &account, true);
+    }
+
+    /// Set the maximum number of holders. Mints and transfers that would bring a new investor above it are rejected. Holders are counted per investor: wallets linked to the same identity count once and protocol contracts do not count.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_holders` - The maximum number of holders, or None to remove the limit.
+    ///
+    #[only_owner]
+    pub fn set_max_holders(e: &Env, max_holders: Option<u32>) {
+        match max_holders {
+            Some(max_holders) => e.storage().instance().set(&MAX_HOLDERS_KEY, &max_holders),
+            None => e.storage().instance().remove(&MAX_HOLDERS_KEY),
+        }
+    }
+
+    /// Add or remove accounts from the holder set according to their balance, and recount them against the maximum number of holders. It is used to backfill holders that were not tracked yet and to pick up identity links made after an account became a holder.
+    ///
+    /// # Arguments
+    ///
+    /// * `accounts` - The accounts to synchronize.
+    ///
+    #[only_owner]
+    pub fn sync_holders(e: &Env, accounts: Vec<Address>) {
+        for account in &accounts {
+            let holder_balance = Self::holder_balance(e, &account);
+            Self::sync_holder(e, &account);
+            Self::add_holder_supply(e, Self::holder_balance(e, &account) - holder_balance);
+            Self::sync_investor(e, &account, true);
+        }
+    }
+
+    /// Enable or disable the local whitelist mirror. When it is enabled, the WHITELISTED_ROLE is checked against the mirror instead of the permission manager. The mirror must be synchronized before it is enabled, and disabled if the token is unregistered from the permission manager.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether the whitelist mirror is used.
+    ///
+    /// # Errors
+    ///
+    /// The caller must be the owner.
+    /// The token must be registered as a mirror in the permission manager to enable it.
+    ///
+    #[only_owner]
+    pub fn set_whitelist_mirror(e: &Env, enabled: bool) {
+        if enabled {
+            assert!(
+                Self::permission_manager_client(e)
+                    .mirrors()
+                    .contains(e.current_contract_address()),
+                "Mirror not registered"
+            );
+        }
+        e.storage().instance().set(&WHITELIST_MIRROR_KEY, &enabled);
+    }
+
+    /// Update the whitelist mirror. It is called once per change by the permission manager when the WHITELISTED_ROLE of accounts changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `accounts` - The addresses of the accounts.
+    /// * `whitelisted` - Whether each account has the WHITELISTED_ROLE for the token.
+    ///
+    /// # Errors
+    ///
+    /// The caller must be the permission manager.
+    /// The accounts and their statuses must have the same length.
+    ///
+    pub fn on_whitelist_update_batch(e: &Env, accounts: Vec<Address>, whitelisted: Vec<bool>) {
+        Self::assert_caller_is_permission_manager(e);
+        assert!(accounts.len() == whitelisted.len(), "Length mismatch");
+        for (account, whitelisted) in accounts.iter().zip(whitelisted.iter()) {
+            Self::set_whitelisted(e, &account, whitelisted);
+        }
+    }
+
+    /// Copy the WHITELISTED_ROLE of accounts from the permission manager to the whitelist mirror. It is used to backfill the mirror and to fix drift.
+    ///
+    /// # Arguments
+    ///
+    /// * `accounts` - The accounts to synchronize.
+    ///
+    #[only_owner]
+    pub fn sync_whitelist(e: &Env, accounts: Vec<Address>) {
+        let client = Self::permission_manager_client(e);
+        let has_roles =
+            client.has_scoped_roles(&accounts, &WHITELISTED_ROLE, &e.current_contract_address());
+        for (account, whitelisted) in accounts.iter().zip(has_roles.iter()) {
+            Self::set_whitelisted(e, &account, whitelisted);
+        }
+    }
+
+    /// Pause a single type of operation, e.g. subscriptions and redemptions while transfers remain allowed. The whole token is paused with `pause`.
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - The operation to pause.
+    /// * `caller` - The address of the caller.
+    ///
+    /// # Errors
+    ///
+    /// The caller must have the PAUSER_ROLE.
+    /// The operation must not be paused.
+    ///
+    pub fn pause_operation(e: &Env, operation: TokenOperation, caller: Address) {
+        caller.require_auth();
+        Self::assert_has_role(e, &caller, &PAUSER_ROLE);
+
+        let key = DataKey::OperationPaused(operation);
+        assert!(!e.storage().instance().has(&key), "Operation paused");
+        e.storage().instance().set(&key, &true);
+
+        e.events()
+            .publish((OPERATION_PAUSED_EVENT,), (operation, caller));
+    }
+
+    /// Unpause a single type of operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - The operation to unpause.
+    /// * `caller` - The address of the caller.
+    ///
+    /// # Errors
+    ///
+    /// The caller must have the PAUSER_ROLE.
+    /// The operation must be paused.
+    ///
+    pub fn unpause_operation(e: &Env, operation: TokenOperation, caller: Address) {
+        caller.require_auth();
+        Self::assert_has_role(e, &caller, &PAUSER_ROLE);
+
+        let key = DataKey::OperationPaused(operation);
+        assert!(e.storage().instance().has(&key), "Operation not paused");
+        e.storage().instance().remove(&key);
+
+        e.events()
+            .publish((OPERATION_UNPAUSED_EVENT,), (operation, caller));
+    }
+
+    /// Get the operations currently paused.
+    pub fn paused_operations(e: &Env) -> Vec<TokenOperation> {
+        let mut operations = Vec::new(e);
+        for operation in TOKEN_OPERATIONS {
+            if e.storage()
+                .instance()
+                .has(&DataKey::OperationPaused(operation))
+            {
+                operations.push_back(operation);
+            }
+        }
+        operations
+    }
+
+    /// Take a snapshot of the balances and of the total supply. Values are checkpointed lazily, on the first change following the snapshot.
+    ///
+    /// # Arguments
+    ///
+    /// * `caller` - The address of the caller.
+    ///
+    /// # Errors
+    ///
+    /// The caller must have the SNAPSHOT_ROLE.
+    ///
+    pub fn snapshot(e: &Env, caller: Address) -> u32 {
+        caller.require_auth();
+        Self::assert_has_role(e, &caller, &SNAPSHOT_ROLE);
+
+        let snapshot_id = Self::current_snapshot_id(e) + 1;
+        e.storage().instance().set(&SNAPSHOT_ID_KEY, &snapshot_id);
+
+        e.events().publish((SNAPSHOT_EVENT,), snapshot_id);
+        snapshot_id
+    }
+
+    fn current_snapshot_id(e: &Env) -> u32 {
+        e.storage().instance().get(&SNAPSHOT_ID_KEY).unwrap_or(0)
+    }
+
+    /// Record the current value under the current snapshot ID if it has not been recorded since the snapshot was taken.
+    fn checkpoint(e: &Env, series: &CheckpointSeries, value: i128) {
+        Self::checkpoint_at(e, series, Self::current_snapshot_id(e), value);
+    }
+
+    fn checkpoint_count(e: &Env, series: &CheckpointSeries) -> u32 {
+        e.storage()
+            .persistent()
+            .get(&DataKey::CheckpointCount(series.clone()))
+            .unwrap_or(0)
+    }
+
+    fn get_checkpoint(e: &Env, series: &CheckpointSeries, index: u32) -> Checkpoint {
+        e.storage()
+            .persistent()
+            .get(&DataKey::Checkpoint(series.clone(), index))
+            .expect("Checkpoint not found")
+    }
+
+    /// Record a value under a snapshot ID if no value has been recorded at or after it.
+    fn checkpoint_at(e: &Env, series: &CheckpointSeries, snapshot_id: u32, value: i128) {
+        if snapshot_id == 0 {
+            return;
+        }
+        let count = Self::checkpoint_count(e, series);
+        let last_snapshot_id = match count {
+            0 => 0,
+            _ => Self::get_checkpoint(e, series, count - 1).0,
+        };
+        if last_snapshot_id < snapshot_id {
+            Self::set_persistent(
+                e,
+                &DataKey::Checkpoint(series.clone(), count),
+                &Checkpoint(snapshot_id, value),
+            );
+            Self::set_persistent(e, &DataKey::CheckpointCount(series.clone()), &(count + 1));
+        }
+    }
+
+    /// Find the value at a snapshot: the first checkpoint recorded at or after it, or the current value if there is none.
+    ///
+    /// # Errors
+    ///
+    /// The snapshot ID must be greater than zero and not greater than the current snapshot ID.
+    ///
+    fn value_at(e: &Env, series: &CheckpointSeries, snapshot_id: u32, current_value: i128) -> i128 {
+        assert!(
+            snapshot_id > 0 && snapshot_id <= Self::current_snapshot_id(e),
+            "Invalid snapshot id"
+        );
+        let count = Self::checkpoint_count(e, series);
+        let mut low = 0;
+        let mut high = count;
+        while low < high {
+            let middle = (low + high) / 2;
+            if Self::get_checkpoint(e, series, middle).0 < snapshot_id {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+        if low < count {
+            Self::get_checkpoint(e, series, low).1
+        } else {
+            current_value
+        }
+    }
+
+    /// Split (or reverse split) the token: every balance and the total supply are multiplied by `numerator / denominator`. The total supply is scaled right away while balances are scaled lazily, on their next change or through `normalize_balances`, and reported scaled in the meantime. Balances are rounded down and the remainders are burned from the total supply as the holders are normalized, so that it ends up equal to the sum of the balances. A new split can only happen once every holder of the previous one is normalized. Pending redemptions and switches must be settled before a split as they are recorded in pre-split amounts.
+    ///
+    /// # Arguments
+    ///
+    /// * `numerator` - The numerator of the split ratio.
+    /// * `denominator` - The denominator of the split ratio.
+    ///
+    /// # Errors
+    ///
+    /// The caller must be the owner.
+    /// The numerator and the denominator must be positive.
+    /// Every holder of the previous split must be normalized.
+    /// Every holder must be in the holder set (see `sync_holders`).
+    /// The redemption contract, if set, must have no pending redemption nor switch of the token and hold none of it.
+    ///
+    #[only_owner]
+    pub fn split(e: &Env, numerator: i128, denominator: i128) {
+        assert!(numerator > 0 && denominator > 0, "Invalid ratio");
+        assert!(Self::pending_normalizations(e) == 0, "Split pending");
+        let redemption: Option<Address> = e.storage().instance().get(&REDEMPTION_KEY);
+        if let Some(redemption) = redemption {
+            let client: RedemptionClient<'_> = RedemptionClient::new(e, &redemption);
+            let token = e.current_contract_address();
+            assert!(
+                client.pending_redemptions(&token) == 0
+                    && client.pending_switches(&token) == 0
+                    && Base::balance(e, &redemption) == 0,
+                "Pending redemptions"
+            );
+        }
+
+        let total_supply = Base::total_supply(e);
+        // Holders missing from the set would never be normalized.
+        assert!(Self::holder_supply(e) == total_supply, "Holders not synced");
+        Self::checkpoint(e, &CheckpointSeries::TotalSupply, total_supply);
+        let scaled_total_supply = total_supply.checked_mul(numerator).expect("Overflow");
+
+        let epoch = Self::split_epoch(e) + 1;
+        let record = SplitRecord {
+            numerator,
+            denominator,
+            snapshot_id: Self::current_snapshot_id(e),
+        };
+        Self::set_persistent(e, &DataKey::Split(epoch), &record);
+        e.storage().instance().set(&SPLIT_EPOCH_KEY, &epoch);
+        e.storage()
+            .instance()
+            .set(&SPLIT_CARRY_KEY, &(scaled_total_supply % denominator));
+        e.storage()
+            .instance()
+            .set(&SPLIT_PENDING_KEY, &Self::holder_count(e));
+        e.storage().instance().set(
+            &StorageKey::TotalSupply,
+            &(scaled_total_supply / denominator),
+        );
+
+        e.events()
+            .publish((SPLIT_EVENT,), (epoch, numerator, denominator));
+    }
+
+    /// Apply the pending split to the balance of accounts. It only settles the rounding of the split and can be called by anyone.
+    ///
+    /// # Arguments
+    ///
+    /// * `accounts` - The accounts to normalize.
+    ///
+    pub fn normalize_balances(e: &Env, accounts: Vec<Address>) {
+        for account in &accounts {
+            Self::normalize(e, &account);
+        }
+    }
+
+    /// Get the number of splits so far.
+    pub fn split_epoch(e: &Env) -> u32 {
+        e.storage().instance().get(&SPLIT_EPOCH_KEY).unwrap_or(0)
+    }
+
+    /// Get a split.
+    ///
+    /// # Arguments
+    ///
+    /// * `epoch` - The split epoch, starting at 1.
+    pub fn get_split(e: &Env, epoch: u32) -> SplitRecord {
+        e.storage()
+            .persistent()
+            .get(&DataKey::Split(epoch))
+            .expect("Split not found")
+    }
+
+    /// Get the number of holders whose balance is not normalized yet for the last split.
+    pub fn pending_normalizations(e: &Env) -> u32 {
+        e.storage().instance().get(&SPLIT_PENDING_KEY).unwrap_or(0)
+    }
+
+    /// The last split not yet applied to the stored balance of an account, with the stored balance. Holders are normalized before the next split, so at most one split can be pending.
+    fn pending_split(e: &Env, account: &Address) -> Option<(SplitRecord, i128)> {
+        let epoch = Self::split_epoch(e);
+        if epoch == 0 {
+            return None;
+        }
+        let account_epoch: u32 = e
+            .storage()
+            .persistent()
+            .get(&DataKey::SplitEpoch(account.clone()))
+            .unwrap_or(0);
+        if account_epoch == epoch {
+            return None;
+        }
+        Some((Self::get_split(e, epoch), Base::balance(e, account)))
+    }
+
+    fn scale(balance: i128, split: &SplitRecord) -> i128 {
+        balance.checked_mul(split.numerator).expect("Overflow") / split.denominator
+    }
+
+    fn scaled_balance(e: &Env, account: &Address) -> i128 {
+        match Self::pending_split(e, account) {
+            Some((split, balance)) => Self::scale(balance, &split),
+            None => Base::balance(e, account),
+        }
+    }
+
+    /// Apply the pending split, if any, to the stored balance of an account and burn its rounding remainder from the total supply. The burned remainder belongs to no holder and is published as a split remainder event.
+    fn normalize(e: &Env, account: &Address) {
+        let Some((split, balance)) = Self::pending_split(e, account) else {
+            return;
+        };
+        Self::set_persistent(
+            e,
+            &DataKey::SplitEpoch(account.clone()),
+            &Self::split_epoch(e),
+        );
+        if balance == 0 {
+            return;
+        }
+
+        let holder_balance = Self::holder_balance(e, account);
+        let scaled_amount = balance.checked_mul(split.numerator).expect("Overflow");
+        let scaled_balance = scaled_amount / split.denominator;
+        let remainder = scaled_amount % split.denominator;
+        // Snapshots taken before the split keep the pre-split balance, later ones the scaled one.
+        let series = CheckpointSeries::Balance(account.clone());
+        Self::checkpoint_at(e, &series, split.snapshot_id, balance);
+        Self::checkpoint(e, &series, scaled_balance);
+        let balance_key = StorageKey::Balance(account.clone());
+        e.storage().persistent().set(&balance_key, &scaled_balance);
+        e.storage().persistent().extend_ttl(
+            &balance_key,
+            BALANCE_TTL_THRESHOLD,
+            BALANCE_EXTEND_AMOUNT,
+        );
+
+        let mut carry: i128 = e.storage().instance().get(&SPLIT_CARRY_KEY).unwrap_or(0) - remainder;
+        if carry < 0 {
+            let burned = (-carry + split.denominator - 1) / split.denominator;
+            let total_supply = Base::total_supply(e);
+            Self::checkpoint(e, &CheckpointSeries::TotalSupply, total_supply);
+            e.storage()
+                .instance()
+                .set(&StorageKey::TotalSupply, &(total_supply - burned));
+            carry += burned * split.denominator;
+            e.events().publish(
+                (SPLIT_EVENT, SPLIT_REMAINDER_EVENT),
+                (Self::split_epoch(e), burned),
+            );
+        }
+        e.storage().instance().set(&SPLIT_CARRY_KEY, &carry);
+        let pending_normalizations = Self::pending_normalizations(e)
+            .checked_sub(1)
+            .expect("No pending normalization");
+        e.storage()
+            .instance()
+            .set(&SPLIT_PENDING_KEY, &pending_normalizations);
+        Self::sync_holder(e, account);
+        Self::add_holder_supply(e, Self::holder_balance(e, account) - holder_balance);
+    }
+
+    /// Update balances and keep track of the holders and of the snapshots. All balance changes must go through it.
+    fn update(e: &Env, from: Option<&Address>, to: Option<&Address>, amount: i128) {
+        for account in [from, to].into_iter().flatten() {
+            Self::normalize(e, account);
+            Self::checkpoint(
+                e,
+                &CheckpointSeries::Balance(account.clone()),
+                Base::balance(e, account),
+            );
+        }
+        if from.is_none() || to.is_none() {
+            Self::checkpoint(e, &CheckpointSeries::TotalSupply, Base::total_supply(e));
+        }
+        let accounts = [from, to.filter(|to| Some(*to) != from)];
+        let holder_balance: i128 = accounts
+            .into_iter()
+            .flatten()
+            .map(|account| Self::holder_balance(e, account))
+            .sum();
+
+        Base::update(e, from, to, amount);
+
+        if let Some(account) = from {
+            Self::sync_holder(e, account);
+        }
+        if let Some(account) = to {
+            Self::sync_holder(e, account);
+        }
+        let updated_holder_balance: i128 = accounts
+            .into_iter()
+            .flatten()
+            .map(|account| Self::holder_balance(e, account))
+            .sum();
+        Self::add_holder_supply(e, updated_holder_balance - holder_balance);
+    }
+
+    /// Get the stored balance of an account if it is in the holder set, zero otherwise.
+    fn holder_balance(e: &Env, account: &Address) -> i128 {
+        if e.storage()
+            .persistent()
+            .has(&DataKey::HolderIndex(account.clone()))
+        {
+            Base::balance(e, account)
+        } else {
+            0
+        }
+    }
+
+    fn add_holder_supply(e: &Env, amount: i128) {
+        if amount != 0 {
+            e.storage()
+                .instance()
+                .set(&HOLDER_SUPPLY_KEY, &(Self::holder_supply(e) + amount));
+        }
+    }
+
+    /// Keep the holder set in line with the balance of an account. Holders are stored one entry per index so that the set can grow to tens of thousands of accounts; removals swap the last holder into the freed index.
+    fn sync_holder(e: &Env, account: &Address) {
+        let index_key = DataKey::HolderIndex(account.clone());
+        let index: Option<u32> = e.storage().persistent().get(&index_key);
+        let is_holder = Base::balance(e, account) > 0;
+        let holder_count = Self::holder_count(e);
+
+        match (index, is_holder) {
+            (None, true) => {
+                Self::set_persistent(e, &DataKey::Holder(holder_count), account);
+                Self::set_persistent(e, &index_key, &holder_count);
+                Self::set_holder_count(e, holder_count + 1);
+            }
+            (Some(index), false) => {
+                let last_index = holder_count - 1;
+                if index != last_index {
+                    let last_holder: Address = e
+                        .storage()
+                        .persistent()
+                        .get(&DataKey::Holder(last_index))
+                        .expect("Holder not found");
+                    Self::set_persistent(e, &DataKey::Holder(index), &last_holder);
+                    Self::set_persistent(e, &DataKey::HolderIndex(last_holder), &index);
+                }
+                e.storage()
+                    .persistent()
+                    .remove(&DataKey::Holder(last_index));
+                e.storage().persistent().remove(&index_key);
+                Self::set_holder_count(e, last_index);
+            }
+            _ => {}
+        }
+        Self::sync_investor(e, account, false);
+    }
+
+    /// Keep the investor count in line with the holder set. The investor of a holder is resolved when it enters the set, or again when `refresh` is set, and remembered so that it is released even if the identity link changes in the meantime.
+    fn sync_investor(e: &Env, account: &Address, refresh: bool) {
+        let key = DataKey::HolderInvestor(account.clone());
+        let counted: Option<Investor> = e.storage().persistent().get(&key);
+        let is_holder = e
+            .storage()
+            .persistent()
+            .has(&DataKey::HolderIndex(account.clone()));
+        if is_holder && counted.is_some() && !refresh {
+            return;
+        }
+
+        let investor = if is_holder {
+            Self::investor_of(e, account)
+        } else {
+            None
+        };
+        if investor == counted {
+            return;
+        }
+        if let Some(counted) = counted {
+            Self::release_investor(e, &counted);
+            e.storage().persistent().remove(&key);
+        }
+        if let Some(investor) = investor {
+            Self::count_investor(e, &investor);
+            Self::set_persistent(e, &key, &investor);
+        }
+    }
+
+    /// Get the investor an account counts as, or None for protocol contracts.
+    fn investor_of(e: &Env, account: &Address) -> Option<Investor> {
+        if Self::is_protocol_account(e, account.clone()) {
+            return None;
+        }
+        let client = Self::permission_manager_client(e);
+        match client.identity_of(account) {
+            Some(identity) => Some(Investor::Identity(identity)),
+            None => Some(Investor::Account(account.clone())),
+        }
+    }
+
+    fn count_investor(e: &Env, investor: &Investor) {
+        if let Investor::Identity(identity) = investor {
+            let key = DataKey::IdentityHolders(identity.clone());
+            let wallets: u32 = e.storage().persistent().get(&key).unwrap_or(0);
+            Self::set_persistent(e, &key, &(wallets + 1));
+            if wallets > 0 {
+                return;
+            }
+        }
+        e.storage()
+            .instance()
+            .set(&INVESTOR_COUNT_KEY, &(Self::investor_count(e) + 1));
+    }
+
+    fn release_investor(e: &Env, investor: &Investor) {
+        if let Investor::Identity(identity) = investor {
+            let key = DataKey::IdentityHolders(identity.clone());
+            let wallets: u32 = e.storage().persistent().get(&key).unwrap_or(0);
+            if wallets > 1 {
+                Self::set_persistent(e, &key, &(wallets - 1));
+                return;
+            }
+            e.storage().persistent().remove(&key);
+        }
+        e.storage()
+            .instance()
+            .set(&INVESTOR_COUNT_KEY, &(Self::investor_count(e) - 1));
+    }
+
+    fn set_holder_count(e: &Env, holder_count: u32) {
+        e.storage().instance().set(&HOLDER_COUNT_KEY, &holder_count);
+    }
+
+    fn set_persistent<V: IntoVal<Env, Val>>(e: &Env, key: &DataKey, value: &V) {
+        e.storage().persistent().set(key, value);
+        e.storage()
+            .persistent()
+            .extend_ttl(key, BALANCE_TTL_THRESHOLD, BALANCE_EXTEND_AMOUNT);
+    }
+
+    /// Assert that the operation did not bring a new investor above the maximum number of holders.
+    ///
+    /// # Arguments
+    ///
+    /// * `investor_count_before` - The number of investors before the operation.
+    ///
+    /// # Errors
+    ///
+    /// The number of investors must not exceed the maximum number of holders when it increased.
+    ///
+    fn assert_max_holders(e: &Env, investor_count_before: u32) {
+        let investor_count = Self::investor_count(e);
+        if investor_count <= investor_count_before {
+            return;
+        }
+        if let Some(max_holders) = Self::max_holders(e) {
+            assert!(investor_count <= max_holders, "Max holders exceeded");
+        }
+    }
+
+    fn mint_no_auth(e: &Env, account: &Address, amount: i128) {
+        Self::update(e, None, Some(account), amount);
+        emit_mint(e, account, amount);
+    }
+
+    fn do_transfer(e: &Env, from: &Address, to: &Address, amount: i128) {
+        from.require_auth();
+        Self::update(e, Some(from), Some(to), amount);
+        emit_transfer(e, from, to, amount);
     }
 
     /// Assert that the idempotency key is not used.
@@ -127,13 +1060,17 @@ impl Token {
     /// The caller must have the MINTER_ROLE.
     /// The account must have the WHITELISTED_ROLE.
     /// The amount must be greater than zero.
+    /// The maximum number of holders must not be exceeded.
     ///
     #[when_not_paused]
     pub fn mint(e: &Env, account: Address, amount: i128, caller: Address) {
+        Self::assert_operation_not_paused(e, TokenOperation::Mint);
         Self::auth_mint(e, caller);
         Self::assert_has_role(e, &account, &WHITELISTED_ROLE);
         assert!(amount > 0, "Invalid zero-amount mint");
-        Base::mint(e, &account, amount);
+        let investor_count_before = Self::investor_count(e);
+        Self::mint_no_auth(e, &account, amount);
+        Self::assert_max_holders(e, investor_count_before);
     }
 
     /// Mint tokens to a batch of accounts.
@@ -151,6 +1088,7 @@ impl Token {
     /// The batch must not be empty.
     /// All accounts must have the WHITELISTED_ROLE.
     /// All amounts must be greater than zero.
+    /// The maximum number of holders must not be exceeded.
     ///
     #[when_not_paused]
     pub fn mint_batch(
@@ -159,18 +1097,26 @@ impl Token {
         caller: Address,
         idempotency_key: String,
     ) {
+        Self::assert_operation_not_paused(e, TokenOperation::Mint);
         Self::auth_mint(e, caller);
         Self::assert_idempotency_key_not_used(e, &idempotency_key);
 
-        assert!(operations.len() > 0, "Empty batch");
+        assert!(!operations.is_empty(), "Empty batch");
 
+        let mut accounts = Vec::new(e);
+        for operation in &operations {
+            accounts.push_back(operation.0);
+        }
+        Self::assert_all_have_role(e, &accounts, &WHITELISTED_ROLE);
+
+        let investor_count_before = Self::investor_count(e);
         for operation in &operations {
             let account = operation.0;
-            Self::assert_has_role(e, &account, &WHITELISTED_ROLE);
             let amount = operation.1;
             assert!(amount > 0, "Invalid zero-amount mint");
-            Base::mint(e, &account, amount);
+            Self::mint_no_auth(e, &account, amount);
         }
+        Self::assert_max_holders(e, investor_count_before);
         Self::consume_idempotency_key(e, &idempotency_key);
     }
 
@@ -180,7 +1126,7 @@ impl Token {
     }
 
     fn burn_no_auth(e: &Env, account: Address, amount: i128) {
-        Base::update(e, Some(&account), None, amount);
+        Self::update(e, Some(&account), None, amount);
         emit_burn(e, &account, amount);
     }
 
@@ -199,6 +1145,7 @@ impl Token {
     ///
     #[when_not_paused]
     pub fn burn(e: &Env, account: Address, amount: i128, caller: Address) {
+        Self::assert_operation_not_paused(e, TokenOperation::Burn);
         Self::auth_burn(e, caller);
         assert!(amount > 0, "Invalid zero-amount burn");
         Self::burn_no_auth(e, account, amount);
@@ -226,9 +1173,10 @@ impl Token {
         caller: Address,
         idempotency_key: String,
     ) {
+        Self::assert_operation_not_paused(e, TokenOperation::Burn);
         Self::auth_burn(e, caller);
         Self::assert_idempotency_key_not_used(e, &idempotency_key);
-        assert!(operations.len() > 0, "Empty batch");
+        assert!(!operations.is_empty(), "Empty batch");
 
         for operation in &operations {
             let account = operation.0;
@@ -253,23 +1201,32 @@ impl Token {
     /// The idempotency key must not be used.
     /// The amount must be greater than zero.
     /// The redemption contract must be set.
+    /// The redemption contract must accept new redemptions of the token.
     /// The redemption contract must have the WHITELISTED_ROLE.
     ///
     #[when_not_paused]
     pub fn redeem(e: &Env, amount: i128, caller: Address, idempotency_key: String) {
+        Self::assert_operation_not_paused(e, TokenOperation::Redeem);
         assert!(amount > 0, "Redemption amount should be more than zero");
-        Self::assert_has_role(e, &caller, &WHITELISTED_ROLE);
-        Self::assert_idempotency_key_not_used(e, &idempotency_key);
-
         let redemption: Address = e
             .storage()
             .instance()
             .get(&REDEMPTION_KEY)
             .expect("Redemption not set");
+        Self::assert_all_have_role(
+            e,
+            &Vec::from_array(e, [caller.clone(), redemption.clone()]),
+            &WHITELISTED_ROLE,
+        );
+        Self::assert_idempotency_key_not_used(e, &idempotency_key);
+
         let client: RedemptionClient<'_> = RedemptionClient::new(e, &redemption);
-        Self::assert_has_role(e, &redemption, &WHITELISTED_ROLE);
+        assert!(
+            client.accepts_redemptions(&e.current_contract_address()),
+            "Redemption not accepted"
+        );
 
-        Base::transfer(e, &caller, &redemption, amount);
+        Self::do_transfer(e, &caller, &redemption, amount);
         client.on_redeem(
             &e.current_contract_address(),
             &caller,
@@ -292,13 +1249,20 @@ impl Token {
     /// The from account must have the WHITELISTED_ROLE.
     /// The to account must have the WHITELISTED_ROLE.
     /// The amount must be greater than zero.
+    /// The maximum number of holders must not be exceeded.
     ///
     #[when_not_paused]
     pub fn transfer(e: &Env, from: Address, to: Address, amount: i128) {
-        Self::assert_has_role(e, &from, &WHITELISTED_ROLE);
-        Self::assert_has_role(e, &to, &WHITELISTED_ROLE);
+        Self::assert_operation_not_paused(e, TokenOperation::Transfer);
+        Self::assert_all_have_role(
+            e,
+            &Vec::from_array(e, [from.clone(), to.clone()]),
+            &WHITELISTED_ROLE,
+        );
         assert!(amount > 0, "Invalid zero-amount transfer");
-        Base::transfer(e, &from, &to, amount);
+        let investor_count_before = Self::investor_count(e);
+        Self::do_transfer(e, &from, &to, amount);
+        Self::assert_max_holders(e, investor_count_before);
     }
 
     /// Transfer tokens from one account to another. The transfer is idempotent.
@@ -316,6 +1280,7 @@ impl Token {
     /// The to account must have the WHITELISTED_ROLE.
     /// The idempotency key must not be used.
     /// The amount must be greater than zero.
+    /// The maximum number of holders must not be exceeded.
     ///
     #[when_not_paused]
     pub fn safe_transfer(
@@ -325,15 +1290,21 @@ impl Token {
         amount: i128,
         idempotency_key: String,
     ) {
-        Self::assert_has_role(e, &from, &WHITELISTED_ROLE);
-        Self::assert_has_role(e, &to, &WHITELISTED_ROLE);
+        Self::assert_operation_not_paused(e, TokenOperation::Transfer);
+        Self::assert_all_have_role(
+            e,
+            &Vec::from_array(e, [from.clone(), to.clone()]),
+            &WHITELISTED_ROLE,
+        );
         Self::assert_idempotency_key_not_used(e, &idempotency_key);
         assert!(amount > 0, "Invalid zero-amount transfer");
-        Base::transfer(e, &from, &to, amount);
+        let investor_count_before = Self::investor_count(e);
+        Self::do_transfer(e, &from, &to, amount);
+        Self::assert_max_holders(e, investor_count_before);
         Self::consume_idempotency_key(e, &idempotency_key);
     }
 
-    /// Get the total supply of tokens.
+    /// Get the total supply of tokens. After a split, it can exceed the sum of the balances by the rounding remainders of the holders not normalized yet.
     pub fn total_supply(e: &Env) -> i128 {
         Base::total_supply(e)
     }
@@ -344,7 +1315,188 @@ impl Token {
     ///
     /// * `account` - The address of the account to get the balance of.
     pub fn balance(e: &Env, account: Address) -> i128 {
-        Base::balance(e, &account)
+        Self::scaled_balance(e, &account)
+    }
+
+    /// Get the balance of an account at a snapshot, in the units of the snapshot if a split happened since.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The address of the account.
+    /// * `snapshot_id` - The snapshot ID returned by `snapshot`.
+    pub fn balance_at(e: &Env, account: Address, snapshot_id: u32) -> i128 {
+        let current_balance = match Self::pending_split(e, &account) {
+            Some((split, balance)) if snapshot_id > split.snapshot_id => {
+                Self::scale(balance, &split)
+            }
+            Some((_, balance)) => balance,
+            None => Base::balance(e, &account),
+        };
+        Self::value_at(
+            e,
+            &CheckpointSeries::Balance(account),
+            snapshot_id,
+            current_balance,
+        )
+    }
+
+    /// Get the total supply at a snapshot, in the units of the snapshot if a split happened since.
+    ///
+    /// # Arguments
+    ///
+    /// * `snapshot_id` - The snapshot ID returned by `snapshot`.
+    pub fn total_supply_at(e: &Env, snapshot_id: u32) -> i128 {
+        Self::value_at(
+            e,
+            &CheckpointSeries::TotalSupply,
+            snapshot_id,
+            Base::total_supply(e),
+        )
+    }
+
+    /// Get the sum of the balances of the holder set. It equals the total supply once every holder is synchronized and normalized.
+    pub fn holder_supply(e: &Env) -> i128 {
+        e.storage().instance().get(&HOLDER_SUPPLY_KEY).unwrap_or(0)
+    }
+
+    /// Get the number of accounts holding a non-zero balance.
+    pub fn holder_count(e: &Env) -> u32 {
+        e.storage().instance().get(&HOLDER_COUNT_KEY).unwrap_or(0)
+    }
+
+    /// Get a page of the current holders with their balance. Indexes are stable within a ledger but a holder leaving the set moves the last holder into its index.
+    ///
+    /// # Arguments
+    ///
+    /// * `cursor` - The index of the first holder to return.
+    /// * `limit` - The maximum number of holders to return. It is capped at 100.
+    pub fn holders(e: &Env, cursor: u32, limit: u32) -> Vec<(Address, i128)> {
+        let end = cursor
+            .saturating_add(limit.min(MAX_HOLDERS_PAGE_SIZE))
+            .min(Self::holder_count(e));
+        let mut holders = Vec::new(e);
+        for index in cursor..end {
+            let key = DataKey::Holder(index);
+            let holder: Address = e
+                .storage()
+                .persistent()
+                .get(&key)
+                .expect("Holder not found");
+            e.storage()
+                .persistent()
+                .extend_ttl(&key, BALANCE_TTL_THRESHOLD, BALANCE_EXTEND_AMOUNT);
+            let balance = Self::scaled_balance(e, &holder);
+            holders.push_back((holder, balance));
+        }
+        holders
+    }
+
+    /// Get the number of investors holding a non-zero balance, as counted against the maximum number of holders.
+    pub fn investor_count(e: &Env) -> u32 {
+        e.storage().instance().get(&INVESTOR_COUNT_KEY).unwrap_or(0)
+    }
+
+    /// Check whether an account is a protocol contract: the redemption contract or an account flagged with `set_protocol_account`.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The address of the account.
+    pub fn is_protocol_account(e: &Env, account: Address) -> bool {
+        let redemption: Option<Address> = e.storage().instance().get(&REDEMPTION_KEY);
+        redemption.as_ref() == Some(&account)
+            || e.storage()
+                .persistent()
+                .has(&DataKey::ProtocolAccount(account))
+    }
+
+    /// Get the maximum number of holders, if any.
+    pub fn max_holders(e: &Env) -> Option<u32> {
+        e.storage().instance().get(&MAX_HOLDERS_KEY)
+    }
+
+    /// Check whether the whitelist mirror is used.
+    pub fn whitelist_mirror(e: &Env) -> bool {
+        e.storage()
+            .instance()
+            .get(&WHITELIST_MIRROR_KEY)
+            .unwrap_or(false)
+    }
+
+    /// Check whether an account is whitelisted in the whitelist mirror.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The address of the account.
+    pub fn is_whitelisted(e: &Env, account: Address) -> bool {
+        let key = DataKey::Whitelisted(account);
+        let whitelisted = e.storage().persistent().has(&key);
+        if whitelisted {
+            e.storage()
+                .persistent()
+                .extend_ttl(&key, BALANCE_TTL_THRESHOLD, BALANCE_EXTEND_AMOUNT);
+        }
+        whitelisted
+    }
+
+    /// Get the accounts whose status in the whitelist mirror differs from their WHITELISTED_ROLE in the permission manager.
+    ///
+    /// # Arguments
+    ///
+    /// * `accounts` - The accounts to reconcile.
+    pub fn whitelist_drift(e: &Env, accounts: Vec<Address>) -> Vec<Address> {
+        let client = Self::permission_manager_client(e);
+        let has_roles =
+            client.has_scoped_roles(&accounts, &WHITELISTED_ROLE, &e.current_contract_address());
+        let mut drift = Vec::new(e);
+        for (account, whitelisted) in accounts.iter().zip(has_roles.iter()) {
+            if Self::is_whitelisted(e, account.clone()) != whitelisted {
+                drift.push_back(account);
+            }
+        }
+        drift
+    }
+
+    /// Get the aggregated balance of all the wallets linked to an identity in the permission manager.
+    ///
+    /// # Arguments
+    ///
+    /// * `identity` - The identity ID.
+    pub fn identity_balance(e: &Env, identity: BytesN<32>) -> i128 {
+        let client = Self::permission_manager_client(e);
+        client
+            .identity_wallets(&identity)
+            .iter()
+            .map(|wallet| Self::scaled_balance(e, &wallet))
+            .sum()
+    }
+
+    /// Get the extended metadata of the fund share class, if set.
+    pub fn fund_info(e: &Env) -> Option<FundInfo> {
+        e.storage().instance().get(&FUND_INFO_KEY)
+    }
+
+    /// Get a document attached to the token, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the document.
+    pub fn get_document(e: &Env, name: Symbol) -> Option<Document> {
+        e.storage().persistent().get(&DataKey::Document(name))
+    }
+
+    /// Get the names of the documents attached to the token.
+    pub fn all_documents(e: &Env) -> Vec<Symbol> {
+        e.storage()
+            .persistent()
+            .get(&DataKey::Documents)
+            .unwrap_or(Vec::new(e))
+    }
+
+    /// Get the pending owner of a two-step ownership transfer, if any. It must call `accept_ownership` before the transfer expires.
+    pub fn pending_owner(e: &Env) -> Option<Address> {
+        e.storage()
+            .temporary()
+            .get(&ownable::OwnableStorageKey::PendingOwner)
     }
 
     pub fn decimals(e: &Env) -> u32 {
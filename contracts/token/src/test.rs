@@ -1,18 +1,25 @@
 #![cfg(test)]
+#![allow(clippy::four_forward_slashes)]
 
 extern crate std;
 
-use crate::contract::{BurnBatchOperation, MintBatchOperation};
-use redemption::ExecuteRedemptionOperation;
+use crate::contract::{BurnBatchOperation, FundInfo, MintBatchOperation, TokenOperation};
+use redemption::{
+    ExecuteRedemptionOperation, ExecuteSwitchOperation, SwitchEntry, SwitchRatioBounds,
+};
 
 use super::contract::{Token, TokenClient};
-use contracts_utils::role::{BURNER_ROLE, MINTER_ROLE, REDEMPTION_EXECUTOR_ROLE, WHITELISTED_ROLE};
+use contracts_utils::role::{
+    BURNER_ROLE, DOC_MANAGER_ROLE, MINTER_ROLE, PAUSER_ROLE, REDEMPTION_EXECUTOR_ROLE,
+    SNAPSHOT_ROLE, WHITELISTED_ROLE,
+};
 use soroban_sdk::{
     symbol_short,
-    testutils::{Address as _, Events},
+    testutils::{Address as _, Events, Ledger},
     xdr::ToXdr,
-    Address, Env, String, Vec,
+    Address, BytesN, Env, String, Symbol, TryFromVal, Vec,
 };
+use stellar_tokens::fungible::StorageKey;
 
 mod permission_manager {
     use soroban_sdk::contractimport;
@@ -32,7 +39,7 @@ fn setup_env() -> Env {
     e
 }
 
-fn deploy_token(e: &Env) -> (Address, Address, TokenClient) {
+fn deploy_token(e: &Env) -> (Address, Address, TokenClient<'_>) {
     let owner: Address = Address::generate(e);
     let name: String = String::from_str(e, "Token");
     let symbol: String = String::from_str(e, "EUTBL");
@@ -75,6 +82,45 @@ fn test_should_set_owner_on_constructor() {
     assert_eq!(fetched_owner, Some(owner));
 }
 
+//// transfer_ownership
+
+#[test]
+fn test_transfer_ownership_should_require_acceptance_by_the_pending_owner() {
+    let e = setup_env();
+    let (owner, _, client) = deploy_token(&e);
+    let new_owner: Address = Address::generate(&e);
+
+    client.transfer_ownership(&new_owner, &(e.ledger().sequence() + 100));
+
+    assert_eq!(client.get_owner(), Some(owner));
+    assert_eq!(client.pending_owner(), Some(new_owner.clone()));
+
+    client.accept_ownership();
+
+    let auths = e.auths();
+    assert_eq!(auths.len(), 1);
+    let (addr, _invocation) = &auths[0];
+    assert_eq!(addr, &new_owner);
+    assert_eq!(client.get_owner(), Some(new_owner));
+    assert_eq!(client.pending_owner(), None);
+}
+
+#[test]
+fn test_accept_ownership_should_fail_after_expiry() {
+    let e = setup_env();
+    let (owner, _, client) = deploy_token(&e);
+    let new_owner: Address = Address::generate(&e);
+    let live_until_ledger = e.ledger().sequence() + 100;
+    client.transfer_ownership(&new_owner, &live_until_ledger);
+
+    e.ledger()
+        .with_mut(|li| li.sequence_number = live_until_ledger + 1);
+    let result = client.try_accept_ownership();
+
+    assert!(result.is_err());
+    assert_eq!(client.get_owner(), Some(owner));
+}
+
 //// set_permission_manager
 
 #[test]
@@ -107,6 +153,56 @@ fn test_set_redemption_should_require_owner_auth() {
     assert_eq!(addr, &owner);
 }
 
+//// set_metadata
+
+#[test]
+fn test_set_metadata_should_require_owner_auth_and_keep_decimals() {
+    let e = setup_env();
+    let (owner, token_address, client) = deploy_token(&e);
+    let name: String = String::from_str(&e, "Epsilon");
+    let symbol: String = String::from_str(&e, "EPS");
+
+    client.set_metadata(&name, &symbol);
+
+    let auths = e.auths();
+    assert_eq!(auths.len(), 1);
+    let (addr, _invocation) = &auths[0];
+    assert_eq!(addr, &owner);
+    let events = e.events().all();
+    let metadata_event = Vec::get(&events, 0).expect("Event should be present");
+    assert_eq!(metadata_event.0, token_address);
+    assert_eq!(
+        metadata_event.2.to_xdr(&e),
+        (name.clone(), symbol.clone()).to_xdr(&e)
+    );
+    assert_eq!(client.name(), name);
+    assert_eq!(client.symbol(), symbol);
+    assert_eq!(client.decimals(), 6);
+}
+
+#[test]
+fn test_set_fund_info_should_require_owner_auth_and_expose_it() {
+    let e = setup_env();
+    let (owner, _, client) = deploy_token(&e);
+    let fund_info = FundInfo {
+        isin: String::from_str(&e, "FR001400XXXX"),
+        fund_lei: String::from_str(&e, "969500XXXXXXXXXXXX00"),
+        share_class_currency: String::from_str(&e, "EUR"),
+        prospectus_uri: String::from_str(&e, "https://example.com/prospectus.pdf"),
+        prospectus_hash: BytesN::from_array(&e, &[1; 32]),
+        kiid_hash: BytesN::from_array(&e, &[2; 32]),
+    };
+    assert_eq!(client.fund_info(), None);
+
+    client.set_fund_info(&fund_info);
+
+    let auths = e.auths();
+    assert_eq!(auths.len(), 1);
+    let (addr, _invocation) = &auths[0];
+    assert_eq!(addr, &owner);
+    assert_eq!(client.fund_info(), Some(fund_info));
+}
+
 //// mint
 
 #[test]
@@ -139,6 +235,28 @@ fn test_mint_should_emit_a_mint_event() {
     assert_eq!(event.2.to_xdr(&e), amount.to_xdr(&e));
 }
 
+#[test]
+fn test_mint_should_accept_roles_scoped_to_the_token_only() {
+    let e = setup_env();
+    let minter: Address = Address::generate(&e);
+    let user: Address = Address::generate(&e);
+    let other_token: Address = Address::generate(&e);
+    let amount: i128 = 1000000;
+    let (_, token_address, client) = deploy_token(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    permission_manager_client.grant_scoped_role(&admin, &minter, &MINTER_ROLE, &other_token);
+    permission_manager_client.grant_scoped_role(&admin, &user, &WHITELISTED_ROLE, &token_address);
+
+    let result = client.try_mint(&user, &amount, &minter);
+    assert!(result.is_err());
+
+    permission_manager_client.grant_scoped_role(&admin, &minter, &MINTER_ROLE, &token_address);
+    client.mint(&user, &amount, &minter);
+    assert_eq!(client.balance(&user), amount);
+}
+
 #[test]
 fn test_mint_should_fail_if_amount_is_not_strictly_positive() {
     let e = setup_env();
@@ -408,6 +526,38 @@ fn test_mint_batch_should_fail_if_idempotency_key_is_already_used() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_assert_all_have_role_should_use_less_budget_than_individual_role_checks() {
+    const N: u32 = 8;
+    let e = setup_env();
+    let (_, token_address, client) = deploy_token(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    let mut users = Vec::new(&e);
+    for _ in 0..N {
+        let user: Address = Address::generate(&e);
+        permission_manager_client.grant_role(&admin, &user, &WHITELISTED_ROLE);
+        users.push_back(user);
+    }
+    let mut budget = e.cost_estimate().budget();
+
+    budget.reset_default();
+    e.as_contract(&token_address, || {
+        for user in &users {
+            Token::assert_has_role(&e, &user, &WHITELISTED_ROLE);
+        }
+    });
+    let individual_cpu_cost = budget.cpu_instruction_cost();
+    budget.reset_default();
+    e.as_contract(&token_address, || {
+        Token::assert_all_have_role(&e, &users, &WHITELISTED_ROLE);
+    });
+    let batched_cpu_cost = budget.cpu_instruction_cost();
+
+    assert!(batched_cpu_cost < individual_cpu_cost);
+}
+
 //// redeem
 
 #[test]
@@ -461,7 +611,7 @@ fn test_redeem_should_require_auth_and_redeem_and_emit_a_redeem_event_and_call_r
         third_transfer_event_topic.to_xdr(&e),
         redemption_address.clone().to_xdr(&e)
     );
-    assert_eq!(transfer_event.2.to_xdr(&e), (amount as i128).to_xdr(&e));
+    assert_eq!(transfer_event.2.to_xdr(&e), amount.to_xdr(&e));
 
     let redemption_event: (Address, Vec<soroban_sdk::Val>, soroban_sdk::Val) =
         Vec::get(&events, 1).expect("Event should be present");
@@ -537,6 +687,32 @@ fn test_redeem_should_fail_if_user_is_not_whitelisted() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_redeem_should_fail_if_token_is_winding_down_in_redemption() {
+    let e = setup_env();
+    let amount: i128 = 1000000;
+    let redeem_idempotency_key: String = String::from_str(&e, "IDEMPOTENCY_KEY");
+    let user: Address = Address::generate(&e);
+    let minter: Address = Address::generate(&e);
+    let (_, token_address, client) = deploy_token(&e);
+    let (_, redemption_address, redemption_client) = deploy_redemption(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    client.set_redemption(&redemption_address);
+    redemption_client.add_token(&token_address);
+    permission_manager_client.grant_role(&admin, &minter, &MINTER_ROLE);
+    permission_manager_client.grant_role(&admin, &user, &WHITELISTED_ROLE);
+    permission_manager_client.grant_role(&admin, &redemption_address, &WHITELISTED_ROLE);
+    client.mint(&user, &amount, &minter);
+    redemption_client.remove_token(&token_address);
+
+    let result = client.try_redeem(&amount, &user, &redeem_idempotency_key);
+
+    assert!(result.is_err());
+    assert_eq!(client.balance(&user), amount);
+}
+
 #[test]
 fn test_redeem_should_fail_if_redemption_contract_is_not_whitelisted() {
     let e = setup_env();
@@ -661,7 +837,7 @@ fn test_safe_transfer_should_transfer_tokens_and_emit_a_transfer_event() {
         third_transfer_event_topic.to_xdr(&e),
         user2.clone().to_xdr(&e)
     );
-    assert_eq!(transfer_event.2.to_xdr(&e), (amount as i128).to_xdr(&e));
+    assert_eq!(transfer_event.2.to_xdr(&e), amount.to_xdr(&e));
 
     let balance1 = client.balance(&user1);
     assert_eq!(balance1, 0);
@@ -825,7 +1001,7 @@ fn test_burn_should_require_auth_and_burn_and_emit_a_burn_event() {
         second_transfer_event_topic.to_xdr(&e),
         redemption_address.clone().to_xdr(&e)
     );
-    assert_eq!(transfer_event.2.to_xdr(&e), (amount as i128).to_xdr(&e));
+    assert_eq!(transfer_event.2.to_xdr(&e), amount.to_xdr(&e));
 
     let balance = client.balance(&redemption_address);
     assert_eq!(balance, 0);
@@ -918,7 +1094,7 @@ fn test_burn_batch_should_require_auth_and_burn_and_emit_a_burn_events() {
         second_event1_topic.to_xdr(&e),
         redemption_address.clone().to_xdr(&e)
     );
-    assert_eq!(event1.2.to_xdr(&e), (amount / 2 as i128).to_xdr(&e));
+    assert_eq!(event1.2.to_xdr(&e), (amount / 2).to_xdr(&e));
 
     let event2 = Vec::get(&events, 0).expect("Event should be present");
     assert_eq!(event2.0, token_address);
@@ -933,7 +1109,7 @@ fn test_burn_batch_should_require_auth_and_burn_and_emit_a_burn_events() {
         second_event2_topic.to_xdr(&e),
         redemption_address.clone().to_xdr(&e)
     );
-    assert_eq!(event2.2.to_xdr(&e), (amount / 2 as i128).to_xdr(&e));
+    assert_eq!(event2.2.to_xdr(&e), (amount / 2).to_xdr(&e));
 
     let balance = client.balance(&redemption_address);
     assert_eq!(balance, 0);
@@ -1078,3 +1254,966 @@ fn test_full_redemption_flow_should_burn_the_token() {
     assert_eq!(user_balance, 9);
     assert_eq!(redemption_balance, 0);
 }
+
+//// Full switch flow
+
+#[test]
+fn test_full_switch_flow_should_burn_the_from_token_and_mint_the_to_token() {
+    let e = setup_env();
+    let user: Address = Address::generate(&e);
+    let executor: Address = Address::generate(&e);
+    let minter: Address = Address::generate(&e);
+    let (_, from_token_address, from_client) = deploy_token(&e);
+    let (_, to_token_address, to_client) = deploy_token(&e);
+    let (_, redemption_address, redemption_client) = deploy_redemption(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    from_client.set_permission_manager(&permission_manager_address);
+    to_client.set_permission_manager(&permission_manager_address);
+    redemption_client.add_token(&from_token_address);
+    redemption_client.add_token(&to_token_address);
+    redemption_client.set_switch_ratio_bounds(
+        &from_token_address,
+        &to_token_address,
+        &Some(SwitchRatioBounds {
+            min_numerator: 9,
+            min_denominator: 10,
+            max_numerator: 11,
+            max_denominator: 10,
+        }),
+    );
+    redemption_client.set_permission_manager(&permission_manager_address);
+    permission_manager_client.grant_role(&admin, &minter, &MINTER_ROLE);
+    permission_manager_client.grant_role(&admin, &executor, &REDEMPTION_EXECUTOR_ROLE);
+    permission_manager_client.grant_role(&admin, &redemption_address, &BURNER_ROLE);
+    permission_manager_client.grant_scoped_role(
+        &admin,
+        &redemption_address,
+        &MINTER_ROLE,
+        &to_token_address,
+    );
+    permission_manager_client.grant_role(&admin, &redemption_address, &WHITELISTED_ROLE);
+    permission_manager_client.grant_role(&admin, &user, &WHITELISTED_ROLE);
+    let switch = SwitchEntry(
+        from_token_address,
+        to_token_address,
+        user.clone(),
+        100,
+        String::from_str(&e, "SALT"),
+    );
+
+    from_client.mint(&user, &1000, &minter);
+    redemption_client.request_switch(&switch);
+    assert_eq!(from_client.balance(&redemption_address), 100);
+    redemption_client.execute_switches(
+        &executor,
+        &Vec::from_array(&e, [ExecuteSwitchOperation(switch, 92, 100)]),
+    );
+
+    assert_eq!(from_client.balance(&user), 900);
+    assert_eq!(from_client.balance(&redemption_address), 0);
+    assert_eq!(to_client.balance(&user), 92);
+}
+
+//// identity_balance
+
+#[test]
+fn test_identity_balance_should_aggregate_balances_of_linked_wallets() {
+    let e = setup_env();
+    let minter: Address = Address::generate(&e);
+    let wallet1: Address = Address::generate(&e);
+    let wallet2: Address = Address::generate(&e);
+    let other: Address = Address::generate(&e);
+    let identity: BytesN<32> = BytesN::from_array(&e, &[1; 32]);
+    let (_, _, client) = deploy_token(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    permission_manager_client.grant_role(&admin, &minter, &MINTER_ROLE);
+    permission_manager_client.grant_role(&admin, &wallet1, &WHITELISTED_ROLE);
+    permission_manager_client.grant_role(&admin, &wallet2, &WHITELISTED_ROLE);
+    permission_manager_client.grant_role(&admin, &other, &WHITELISTED_ROLE);
+    permission_manager_client.link_wallet(&admin, &wallet1, &identity);
+    permission_manager_client.link_wallet(&admin, &wallet2, &identity);
+
+    client.mint(&wallet1, &100, &minter);
+    client.mint(&wallet2, &200, &minter);
+    client.mint(&other, &400, &minter);
+
+    assert_eq!(client.identity_balance(&identity), 300);
+}
+
+#[test]
+fn test_identity_balance_should_be_zero_for_unknown_identity() {
+    let e = setup_env();
+    let identity: BytesN<32> = BytesN::from_array(&e, &[1; 32]);
+    let (_, _, client) = deploy_token(&e);
+    let (_, permission_manager_address, _) = deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+
+    assert_eq!(client.identity_balance(&identity), 0);
+}
+
+//// holder_count
+
+#[test]
+fn test_holder_count_should_track_accounts_crossing_zero() {
+    let e = setup_env();
+    let minter: Address = Address::generate(&e);
+    let burner: Address = Address::generate(&e);
+    let user1: Address = Address::generate(&e);
+    let user2: Address = Address::generate(&e);
+    let (_, _, client) = deploy_token(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    permission_manager_client.grant_role(&admin, &minter, &MINTER_ROLE);
+    permission_manager_client.grant_role(&admin, &burner, &BURNER_ROLE);
+    permission_manager_client.grant_role(&admin, &user1, &WHITELISTED_ROLE);
+    permission_manager_client.grant_role(&admin, &user2, &WHITELISTED_ROLE);
+
+    client.mint(&user1, &100, &minter);
+    client.mint(&user1, &100, &minter);
+    assert_eq!(client.holder_count(), 1);
+
+    client.transfer(&user1, &user2, &50);
+    assert_eq!(client.holder_count(), 2);
+
+    client.transfer(&user2, &user1, &50);
+    assert_eq!(client.holder_count(), 1);
+
+    client.burn(&user1, &200, &burner);
+    assert_eq!(client.holder_count(), 0);
+}
+
+//// set_max_holders
+
+#[test]
+fn test_set_max_holders_should_require_owner_auth() {
+    let e = setup_env();
+    let (owner, _, client) = deploy_token(&e);
+
+    client.set_max_holders(&Some(10));
+
+    let auths = e.auths();
+    assert_eq!(auths.len(), 1);
+    let (addr, _invocation) = &auths[0];
+    assert_eq!(addr, &owner);
+    assert_eq!(client.max_holders(), Some(10));
+}
+
+#[test]
+fn test_mint_should_fail_if_max_holders_is_reached() {
+    let e = setup_env();
+    let minter: Address = Address::generate(&e);
+    let user1: Address = Address::generate(&e);
+    let user2: Address = Address::generate(&e);
+    let (_, _, client) = deploy_token(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    client.set_max_holders(&Some(1));
+    permission_manager_client.grant_role(&admin, &minter, &MINTER_ROLE);
+    permission_manager_client.grant_role(&admin, &user1, &WHITELISTED_ROLE);
+    permission_manager_client.grant_role(&admin, &user2, &WHITELISTED_ROLE);
+    client.mint(&user1, &100, &minter);
+
+    let result = client.try_mint(&user2, &100, &minter);
+
+    assert!(result.is_err());
+    client.mint(&user1, &100, &minter);
+    assert_eq!(client.balance(&user1), 200);
+}
+
+#[test]
+fn test_transfer_should_fail_if_max_holders_is_reached() {
+    let e = setup_env();
+    let minter: Address = Address::generate(&e);
+    let user1: Address = Address::generate(&e);
+    let user2: Address = Address::generate(&e);
+    let (_, _, client) = deploy_token(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    client.set_max_holders(&Some(1));
+    permission_manager_client.grant_role(&admin, &minter, &MINTER_ROLE);
+    permission_manager_client.grant_role(&admin, &user1, &WHITELISTED_ROLE);
+    permission_manager_client.grant_role(&admin, &user2, &WHITELISTED_ROLE);
+    client.mint(&user1, &100, &minter);
+
+    let result = client.try_transfer(&user1, &user2, &50);
+    assert!(result.is_err());
+
+    client.transfer(&user1, &user2, &100);
+    assert_eq!(client.balance(&user2), 100);
+    assert_eq!(client.holder_count(), 1);
+}
+
+#[test]
+fn test_max_holders_should_not_count_the_redemption_contract_and_protocol_accounts() {
+    let e = setup_env();
+    let minter: Address = Address::generate(&e);
+    let user1: Address = Address::generate(&e);
+    let user2: Address = Address::generate(&e);
+    let distribution: Address = Address::generate(&e);
+    let redeem_idempotency_key: String = String::from_str(&e, "IDEMPOTENCY_KEY");
+    let (_, token_address, client) = deploy_token(&e);
+    let (_, redemption_address, redemption_client) = deploy_redemption(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    client.set_redemption(&redemption_address);
+    client.set_protocol_account(&distribution, &true);
+    client.set_max_holders(&Some(2));
+    redemption_client.add_token(&token_address);
+    redemption_client.set_permission_manager(&permission_manager_address);
+    permission_manager_client.grant_role(&admin, &minter, &MINTER_ROLE);
+    permission_manager_client.grant_role(&admin, &redemption_address, &WHITELISTED_ROLE);
+    permission_manager_client.grant_role(&admin, &distribution, &WHITELISTED_ROLE);
+    permission_manager_client.grant_role(&admin, &user1, &WHITELISTED_ROLE);
+    permission_manager_client.grant_role(&admin, &user2, &WHITELISTED_ROLE);
+
+    client.mint(&user1, &100, &minter);
+    client.redeem(&10, &user1, &redeem_idempotency_key);
+    client.transfer(&user1, &distribution, &10);
+    client.mint(&user2, &100, &minter);
+
+    assert_eq!(client.holder_count(), 4);
+    assert_eq!(client.investor_count(), 2);
+    assert!(client.is_protocol_account(&redemption_address));
+    assert!(client.is_protocol_account(&distribution));
+}
+
+#[test]
+fn test_max_holders_should_count_wallets_linked_to_an_identity_once() {
+    let e = setup_env();
+    let minter: Address = Address::generate(&e);
+    let wallet1: Address = Address::generate(&e);
+    let wallet2: Address = Address::generate(&e);
+    let other: Address = Address::generate(&e);
+    let identity: BytesN<32> = BytesN::from_array(&e, &[1; 32]);
+    let (_, _, client) = deploy_token(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    client.set_max_holders(&Some(1));
+    permission_manager_client.grant_role(&admin, &minter, &MINTER_ROLE);
+    permission_manager_client.grant_role(&admin, &wallet1, &WHITELISTED_ROLE);
+    permission_manager_client.grant_role(&admin, &wallet2, &WHITELISTED_ROLE);
+    permission_manager_client.grant_role(&admin, &other, &WHITELISTED_ROLE);
+    permission_manager_client.link_wallet(&admin, &wallet1, &identity);
+    permission_manager_client.link_wallet(&admin, &wallet2, &identity);
+
+    client.mint(&wallet1, &100, &minter);
+    client.mint(&wallet2, &100, &minter);
+    let result = client.try_mint(&other, &100, &minter);
+
+    assert!(result.is_err());
+    assert_eq!(client.holder_count(), 2);
+    assert_eq!(client.investor_count(), 1);
+
+    client.transfer(&wallet1, &wallet2, &100);
+    client.transfer(&wallet2, &other, &200);
+    assert_eq!(client.investor_count(), 1);
+}
+
+//// holders
+
+#[test]
+fn test_holders_should_return_paginated_holders_with_balances() {
+    let e = setup_env();
+    let minter: Address = Address::generate(&e);
+    let user1: Address = Address::generate(&e);
+    let user2: Address = Address::generate(&e);
+    let user3: Address = Address::generate(&e);
+    let (_, _, client) = deploy_token(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    permission_manager_client.grant_role(&admin, &minter, &MINTER_ROLE);
+    permission_manager_client.grant_role(&admin, &user1, &WHITELISTED_ROLE);
+    permission_manager_client.grant_role(&admin, &user2, &WHITELISTED_ROLE);
+    permission_manager_client.grant_role(&admin, &user3, &WHITELISTED_ROLE);
+    client.mint(&user1, &100, &minter);
+    client.mint(&user2, &200, &minter);
+    client.mint(&user3, &300, &minter);
+
+    let first_page = client.holders(&0, &2);
+    let second_page = client.holders(&2, &2);
+
+    assert_eq!(first_page.len(), 2);
+    assert_eq!(first_page.get(0), Some((user1.clone(), 100)));
+    assert_eq!(first_page.get(1), Some((user2.clone(), 200)));
+    assert_eq!(second_page.len(), 1);
+    assert_eq!(second_page.get(0), Some((user3.clone(), 300)));
+}
+
+#[test]
+fn test_holders_should_move_last_holder_when_a_holder_leaves() {
+    let e = setup_env();
+    let minter: Address = Address::generate(&e);
+    let user1: Address = Address::generate(&e);
+    let user2: Address = Address::generate(&e);
+    let user3: Address = Address::generate(&e);
+    let (_, _, client) = deploy_token(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    permission_manager_client.grant_role(&admin, &minter, &MINTER_ROLE);
+    permission_manager_client.grant_role(&admin, &user1, &WHITELISTED_ROLE);
+    permission_manager_client.grant_role(&admin, &user2, &WHITELISTED_ROLE);
+    permission_manager_client.grant_role(&admin, &user3, &WHITELISTED_ROLE);
+    client.mint(&user1, &100, &minter);
+    client.mint(&user2, &200, &minter);
+    client.mint(&user3, &300, &minter);
+
+    client.transfer(&user1, &user2, &100);
+
+    let holders = client.holders(&0, &10);
+    assert_eq!(holders.len(), 2);
+    assert_eq!(holders.get(0), Some((user3.clone(), 300)));
+    assert_eq!(holders.get(1), Some((user2.clone(), 300)));
+    assert_eq!(client.holder_count(), 2);
+}
+
+//// sync_holders
+
+#[test]
+fn test_sync_holders_should_require_owner_auth() {
+    let e = setup_env();
+    let user: Address = Address::generate(&e);
+    let (owner, _, client) = deploy_token(&e);
+    let mut accounts = Vec::new(&e);
+    accounts.push_back(user.clone());
+
+    client.sync_holders(&accounts);
+
+    let auths = e.auths();
+    assert_eq!(auths.len(), 1);
+    let (addr, _invocation) = &auths[0];
+    assert_eq!(addr, &owner);
+    assert_eq!(client.holder_count(), 0);
+}
+
+//// snapshot
+
+#[test]
+fn test_snapshot_should_fail_if_not_snapshot_role() {
+    let e = setup_env();
+    let caller: Address = Address::generate(&e);
+    let (_, _, client) = deploy_token(&e);
+    let (_, permission_manager_address, _) = deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+
+    let result = client.try_snapshot(&caller);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_snapshot_should_return_increasing_ids() {
+    let e = setup_env();
+    let caller: Address = Address::generate(&e);
+    let (_, _, client) = deploy_token(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    permission_manager_client.grant_role(&admin, &caller, &SNAPSHOT_ROLE);
+
+    assert_eq!(client.snapshot(&caller), 1);
+    assert_eq!(client.snapshot(&caller), 2);
+}
+
+#[test]
+fn test_balance_at_and_total_supply_at_should_return_values_at_snapshot() {
+    let e = setup_env();
+    let minter: Address = Address::generate(&e);
+    let burner: Address = Address::generate(&e);
+    let snapshotter: Address = Address::generate(&e);
+    let user1: Address = Address::generate(&e);
+    let user2: Address = Address::generate(&e);
+    let (_, _, client) = deploy_token(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    permission_manager_client.grant_role(&admin, &minter, &MINTER_ROLE);
+    permission_manager_client.grant_role(&admin, &burner, &BURNER_ROLE);
+    permission_manager_client.grant_role(&admin, &snapshotter, &SNAPSHOT_ROLE);
+    permission_manager_client.grant_role(&admin, &user1, &WHITELISTED_ROLE);
+    permission_manager_client.grant_role(&admin, &user2, &WHITELISTED_ROLE);
+    client.mint(&user1, &100, &minter);
+
+    let snapshot1 = client.snapshot(&snapshotter);
+    client.transfer(&user1, &user2, &40);
+    client.mint(&user2, &10, &minter);
+    let snapshot2 = client.snapshot(&snapshotter);
+    let snapshot3 = client.snapshot(&snapshotter);
+    client.burn(&user1, &60, &burner);
+
+    assert_eq!(client.balance_at(&user1, &snapshot1), 100);
+    assert_eq!(client.balance_at(&user2, &snapshot1), 0);
+    assert_eq!(client.total_supply_at(&snapshot1), 100);
+    assert_eq!(client.balance_at(&user1, &snapshot2), 60);
+    assert_eq!(client.balance_at(&user2, &snapshot2), 50);
+    assert_eq!(client.total_supply_at(&snapshot2), 110);
+    assert_eq!(client.balance_at(&user1, &snapshot3), 60);
+    assert_eq!(client.total_supply_at(&snapshot3), 110);
+    assert_eq!(client.balance(&user1), 0);
+    assert_eq!(client.total_supply(), 50);
+}
+
+#[test]
+fn test_checkpoints_should_not_grow_the_cost_of_balance_changes() {
+    let e = setup_env();
+    let minter: Address = Address::generate(&e);
+    let snapshotter: Address = Address::generate(&e);
+    let user: Address = Address::generate(&e);
+    let (_, _, client) = deploy_token(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    permission_manager_client.grant_role(&admin, &minter, &MINTER_ROLE);
+    permission_manager_client.grant_role(&admin, &snapshotter, &SNAPSHOT_ROLE);
+    permission_manager_client.grant_role(&admin, &user, &WHITELISTED_ROLE);
+    client.mint(&user, &1, &minter);
+
+    client.snapshot(&snapshotter);
+    client.mint(&user, &1, &minter);
+    let first_write_bytes = e.cost_estimate().resources().write_bytes;
+    for _ in 0..40 {
+        client.snapshot(&snapshotter);
+        client.mint(&user, &1, &minter);
+    }
+    let last_write_bytes = e.cost_estimate().resources().write_bytes;
+
+    assert!(last_write_bytes <= first_write_bytes);
+    assert_eq!(client.balance_at(&user, &1), 1);
+    assert_eq!(client.balance_at(&user, &20), 20);
+    assert_eq!(client.total_supply_at(&41), 41);
+    assert_eq!(client.balance(&user), 42);
+}
+
+#[test]
+fn test_balance_at_should_fail_if_snapshot_does_not_exist() {
+    let e = setup_env();
+    let user: Address = Address::generate(&e);
+    let (_, _, client) = deploy_token(&e);
+
+    assert!(client.try_balance_at(&user, &0).is_err());
+    assert!(client.try_balance_at(&user, &1).is_err());
+}
+
+//// whitelist_mirror
+
+#[test]
+fn test_on_whitelist_update_should_mirror_the_whitelisted_role() {
+    let e = setup_env();
+    let user: Address = Address::generate(&e);
+    let (_, token_address, client) = deploy_token(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    permission_manager_client.register_mirror(&token_address);
+
+    permission_manager_client.grant_role(&admin, &user, &WHITELISTED_ROLE);
+    assert!(client.is_whitelisted(&user));
+
+    permission_manager_client.revoke_role(&admin, &user, &WHITELISTED_ROLE);
+    assert!(!client.is_whitelisted(&user));
+}
+
+#[test]
+fn test_on_whitelist_update_batch_should_require_permission_manager_auth() {
+    let e = setup_env();
+    let user: Address = Address::generate(&e);
+    let (_, _, client) = deploy_token(&e);
+    let (_, permission_manager_address, _) = deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+
+    client.on_whitelist_update_batch(
+        &Vec::from_array(&e, [user.clone()]),
+        &Vec::from_array(&e, [true]),
+    );
+
+    let auths = e.auths();
+    assert_eq!(auths.len(), 1);
+    let (addr, _invocation) = &auths[0];
+    assert_eq!(addr, &permission_manager_address);
+}
+
+#[test]
+fn test_mint_should_check_the_whitelist_mirror_when_enabled() {
+    let e = setup_env();
+    let minter: Address = Address::generate(&e);
+    let user: Address = Address::generate(&e);
+    let (_, token_address, client) = deploy_token(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    permission_manager_client.grant_role(&admin, &minter, &MINTER_ROLE);
+    permission_manager_client.grant_role(&admin, &user, &WHITELISTED_ROLE);
+    permission_manager_client.register_mirror(&token_address);
+    client.set_whitelist_mirror(&true);
+
+    let result = client.try_mint(&user, &100, &minter);
+    assert!(result.is_err());
+    let users = Vec::from_array(&e, [user.clone()]);
+    assert_eq!(client.whitelist_drift(&users), users);
+
+    client.sync_whitelist(&users);
+    assert_eq!(client.whitelist_drift(&users), Vec::new(&e));
+    client.mint(&user, &100, &minter);
+    assert_eq!(client.balance(&user), 100);
+}
+
+#[test]
+fn test_set_whitelist_mirror_should_fail_if_the_token_is_not_registered() {
+    let e = setup_env();
+    let (_, token_address, client) = deploy_token(&e);
+    let (_, permission_manager_address, permission_manager_client) = deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+
+    assert!(client.try_set_whitelist_mirror(&true).is_err());
+
+    permission_manager_client.register_mirror(&token_address);
+    client.set_whitelist_mirror(&true);
+    client.set_whitelist_mirror(&false);
+    permission_manager_client.unregister_mirror(&token_address);
+    client.set_whitelist_mirror(&false);
+}
+
+//// pause_all
+
+#[test]
+fn test_transfer_and_mint_should_fail_if_globally_paused() {
+    let e = setup_env();
+    let minter: Address = Address::generate(&e);
+    let pauser: Address = Address::generate(&e);
+    let user1: Address = Address::generate(&e);
+    let user2: Address = Address::generate(&e);
+    let (_, _, client) = deploy_token(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    permission_manager_client.grant_role(&admin, &minter, &MINTER_ROLE);
+    permission_manager_client.grant_role(&admin, &pauser, &PAUSER_ROLE);
+    permission_manager_client.grant_role(&admin, &user1, &WHITELISTED_ROLE);
+    permission_manager_client.grant_role(&admin, &user2, &WHITELISTED_ROLE);
+    client.mint(&user1, &100, &minter);
+
+    permission_manager_client.pause_all(&pauser);
+
+    assert!(!client.paused());
+    assert!(client.try_transfer(&user1, &user2, &10).is_err());
+    assert!(client.try_mint(&user1, &100, &minter).is_err());
+
+    permission_manager_client.unpause_all(&pauser);
+    client.transfer(&user1, &user2, &10);
+    assert_eq!(client.balance(&user2), 10);
+}
+
+#[test]
+fn test_transfer_and_mint_should_check_the_circuit_breaker_when_the_mirror_is_enabled() {
+    let e = setup_env();
+    let minter: Address = Address::generate(&e);
+    let pauser: Address = Address::generate(&e);
+    let user1: Address = Address::generate(&e);
+    let user2: Address = Address::generate(&e);
+    let (_, token_address, client) = deploy_token(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    permission_manager_client.register_mirror(&token_address);
+    permission_manager_client.grant_role(&admin, &minter, &MINTER_ROLE);
+    permission_manager_client.grant_role(&admin, &pauser, &PAUSER_ROLE);
+    permission_manager_client.grant_role(&admin, &user1, &WHITELISTED_ROLE);
+    permission_manager_client.grant_role(&admin, &user2, &WHITELISTED_ROLE);
+    client.set_whitelist_mirror(&true);
+
+    permission_manager_client.pause_all(&pauser);
+    assert!(client.try_mint(&user1, &100, &minter).is_err());
+
+    permission_manager_client.unpause_all(&pauser);
+    client.mint(&user1, &100, &minter);
+    permission_manager_client.unregister_mirror(&token_address);
+
+    permission_manager_client.pause_all(&pauser);
+    assert!(client.try_transfer(&user1, &user2, &10).is_err());
+
+    permission_manager_client.unpause_all(&pauser);
+    client.transfer(&user1, &user2, &10);
+    assert_eq!(client.balance(&user2), 10);
+}
+
+//// pause_operation
+
+#[test]
+fn test_pause_operation_should_only_pause_the_operation() {
+    let e = setup_env();
+    let minter: Address = Address::generate(&e);
+    let pauser: Address = Address::generate(&e);
+    let user1: Address = Address::generate(&e);
+    let user2: Address = Address::generate(&e);
+    let (_, _, client) = deploy_token(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    permission_manager_client.grant_role(&admin, &minter, &MINTER_ROLE);
+    permission_manager_client.grant_role(&admin, &pauser, &PAUSER_ROLE);
+    permission_manager_client.grant_role(&admin, &user1, &WHITELISTED_ROLE);
+    permission_manager_client.grant_role(&admin, &user2, &WHITELISTED_ROLE);
+    client.mint(&user1, &100, &minter);
+
+    client.pause_operation(&TokenOperation::Mint, &pauser);
+    client.pause_operation(&TokenOperation::Redeem, &pauser);
+
+    assert_eq!(
+        client.paused_operations(),
+        Vec::from_array(&e, [TokenOperation::Mint, TokenOperation::Redeem])
+    );
+    assert!(client.try_mint(&user1, &100, &minter).is_err());
+    client.transfer(&user1, &user2, &10);
+    assert_eq!(client.balance(&user2), 10);
+
+    client.unpause_operation(&TokenOperation::Mint, &pauser);
+    client.mint(&user1, &100, &minter);
+    assert_eq!(
+        client.paused_operations(),
+        Vec::from_array(&e, [TokenOperation::Redeem])
+    );
+}
+
+#[test]
+fn test_pause_operation_should_fail_if_caller_is_not_pauser() {
+    let e = setup_env();
+    let caller: Address = Address::generate(&e);
+    let (_, _, client) = deploy_token(&e);
+    let (_, permission_manager_address, _) = deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+
+    let result = client.try_pause_operation(&TokenOperation::Transfer, &caller);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_unpause_operation_should_fail_if_operation_is_not_paused() {
+    let e = setup_env();
+    let pauser: Address = Address::generate(&e);
+    let (_, _, client) = deploy_token(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    permission_manager_client.grant_role(&admin, &pauser, &PAUSER_ROLE);
+
+    let result = client.try_unpause_operation(&TokenOperation::Burn, &pauser);
+
+    assert!(result.is_err());
+}
+
+//// split
+
+fn deploy_token_with_holders<'a>(
+    e: &'a Env,
+    balances: &[i128],
+) -> (
+    Address,
+    Address,
+    permission_manager::Client<'a>,
+    TokenClient<'a>,
+    Vec<Address>,
+) {
+    let minter: Address = Address::generate(e);
+    let (_, _, client) = deploy_token(e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(e);
+    client.set_permission_manager(&permission_manager_address);
+    permission_manager_client.grant_role(&admin, &minter, &MINTER_ROLE);
+    let mut holders = Vec::new(e);
+    for balance in balances {
+        let holder: Address = Address::generate(e);
+        permission_manager_client.grant_role(&admin, &holder, &WHITELISTED_ROLE);
+        client.mint(&holder, balance, &minter);
+        holders.push_back(holder);
+    }
+
+    (admin, minter, permission_manager_client, client, holders)
+}
+
+#[test]
+fn test_split_should_require_owner_auth_and_scale_balances_and_total_supply() {
+    let e = setup_env();
+    let (_, _, _, client, holders) = deploy_token_with_holders(&e, &[10, 25]);
+    let owner = client.get_owner().unwrap();
+
+    client.split(&2, &1);
+
+    let auths = e.auths();
+    assert_eq!(auths.len(), 1);
+    let (addr, _invocation) = &auths[0];
+    assert_eq!(addr, &owner);
+    let events = e.events().all();
+    let split_event = Vec::get(&events, Vec::len(&events) - 1).expect("Event should be present");
+    assert_eq!(split_event.2.to_xdr(&e), (1_u32, 2_i128, 1_i128).to_xdr(&e));
+    assert_eq!(client.balance(&holders.get(0).unwrap()), 20);
+    assert_eq!(client.balance(&holders.get(1).unwrap()), 50);
+    assert_eq!(client.total_supply(), 70);
+    assert_eq!(client.split_epoch(), 1);
+    assert_eq!(client.pending_normalizations(), 2);
+}
+
+#[test]
+fn test_split_should_round_balances_down_and_burn_the_remainders_on_normalization() {
+    let e = setup_env();
+    let (_, _, _, client, holders) = deploy_token_with_holders(&e, &[5, 5, 5]);
+
+    client.split(&1, &2);
+
+    assert_eq!(client.total_supply(), 7);
+    for holder in holders.iter() {
+        assert_eq!(client.balance(&holder), 2);
+    }
+    let result = client.try_split(&1, &2);
+    assert!(result.is_err());
+
+    client.normalize_balances(&holders);
+
+    let events = e.events().all();
+    let remainder_events: std::vec::Vec<(u32, i128)> = events
+        .iter()
+        .filter(|(_, topics, _)| {
+            topics.clone().to_xdr(&e)
+                == Vec::<soroban_sdk::Val>::from_array(
+                    &e,
+                    [
+                        symbol_short!("SPLIT").to_val(),
+                        symbol_short!("REMAINDER").to_val(),
+                    ],
+                )
+                .to_xdr(&e)
+        })
+        .map(|(_, _, data)| <(u32, i128)>::try_from_val(&e, &data).unwrap())
+        .collect();
+    assert_eq!(remainder_events, std::vec![(1, 1)]);
+    assert!(!events.iter().any(|(_, topics, _)| {
+        topics.get_unchecked(0).to_xdr(&e) == symbol_short!("burn").to_xdr(&e)
+    }));
+    assert_eq!(client.total_supply(), 6);
+    assert_eq!(client.pending_normalizations(), 0);
+    for holder in holders.iter() {
+        assert_eq!(client.balance(&holder), 2);
+    }
+    client.split(&3, &1);
+    assert_eq!(client.total_supply(), 18);
+}
+
+#[test]
+fn test_split_should_fail_until_every_holder_is_synced() {
+    let e = setup_env();
+    let (_, _, _, client, _) = deploy_token_with_holders(&e, &[10, 10]);
+    let legacy_holder: Address = Address::generate(&e);
+    e.as_contract(&client.address, || {
+        e.storage()
+            .persistent()
+            .set(&StorageKey::Balance(legacy_holder.clone()), &100_i128);
+        e.storage()
+            .instance()
+            .set(&StorageKey::TotalSupply, &120_i128);
+    });
+    assert_eq!(client.holder_supply(), 20);
+
+    let result = client.try_split(&2, &1);
+    assert!(result.is_err());
+
+    client.sync_holders(&Vec::from_array(&e, [legacy_holder.clone()]));
+    assert_eq!(client.holder_supply(), 120);
+    client.split(&2, &1);
+    client.normalize_balances(&Vec::from_array(&e, [legacy_holder.clone()]));
+    assert_eq!(client.balance(&legacy_holder), 200);
+    assert_eq!(client.holder_supply(), 220);
+    assert_eq!(client.pending_normalizations(), 2);
+}
+
+#[test]
+fn test_split_should_fail_while_the_redemption_contract_has_pending_redemptions() {
+    let e = setup_env();
+    let executor: Address = Address::generate(&e);
+    let salt: String = String::from_str(&e, "IDEMPOTENCY_KEY");
+    let (admin, _, permission_manager_client, client, holders) =
+        deploy_token_with_holders(&e, &[100, 100]);
+    let (_, redemption_address, redemption_client) = deploy_redemption(&e);
+    client.set_redemption(&redemption_address);
+    redemption_client.add_token(&client.address);
+    redemption_client.set_permission_manager(&permission_manager_client.address);
+    permission_manager_client.grant_role(&admin, &redemption_address, &WHITELISTED_ROLE);
+    permission_manager_client.grant_role(&admin, &redemption_address, &BURNER_ROLE);
+    permission_manager_client.grant_role(&admin, &executor, &REDEMPTION_EXECUTOR_ROLE);
+    let user = holders.get(0).unwrap();
+    client.redeem(&40, &user, &salt);
+
+    assert!(client.try_split(&2, &1).is_err());
+
+    redemption_client.execute_redemptions(
+        &executor,
+        &Vec::from_array(
+            &e,
+            [ExecuteRedemptionOperation(
+                client.address.clone(),
+                user.clone(),
+                40,
+                salt,
+            )],
+        ),
+    );
+    client.split(&2, &1);
+    assert_eq!(client.total_supply(), 320);
+    client.normalize_balances(&holders);
+    client.transfer(&user, &redemption_address, &10);
+    assert!(client.try_split(&2, &1).is_err());
+}
+
+#[test]
+fn test_split_should_remove_holders_rounded_down_to_zero() {
+    let e = setup_env();
+    let (_, _, _, client, holders) = deploy_token_with_holders(&e, &[1, 100]);
+
+    client.split(&1, &10);
+    client.normalize_balances(&holders);
+
+    assert_eq!(client.balance(&holders.get(0).unwrap()), 0);
+    assert_eq!(client.holder_count(), 1);
+    assert_eq!(client.total_supply(), 10);
+}
+
+#[test]
+fn test_transfer_after_split_should_use_post_split_amounts() {
+    let e = setup_env();
+    let (_, _, _, client, holders) = deploy_token_with_holders(&e, &[10, 10]);
+    let from = holders.get(0).unwrap();
+    let to = holders.get(1).unwrap();
+
+    client.split(&3, &2);
+    client.transfer(&from, &to, &15);
+
+    let events = e.events().all();
+    let transfer_event = Vec::get(&events, Vec::len(&events) - 1).expect("Event should be present");
+    assert_eq!(transfer_event.2.to_xdr(&e), 15_i128.to_xdr(&e));
+    assert_eq!(client.balance(&from), 0);
+    assert_eq!(client.balance(&to), 30);
+    assert_eq!(client.total_supply(), 30);
+    assert_eq!(client.pending_normalizations(), 0);
+}
+
+#[test]
+fn test_balance_at_should_report_snapshot_values_in_the_units_of_the_snapshot() {
+    let e = setup_env();
+    let snapshotter: Address = Address::generate(&e);
+    let (admin, minter, permission_manager_client, client, holders) =
+        deploy_token_with_holders(&e, &[10, 20]);
+    let holder1 = holders.get(0).unwrap();
+    let holder2 = holders.get(1).unwrap();
+    permission_manager_client.grant_role(&admin, &snapshotter, &SNAPSHOT_ROLE);
+
+    let before_split = client.snapshot(&snapshotter);
+    client.split(&2, &1);
+    let after_split = client.snapshot(&snapshotter);
+    client.mint(&holder1, &1, &minter);
+
+    assert_eq!(client.balance_at(&holder1, &before_split), 10);
+    assert_eq!(client.balance_at(&holder2, &before_split), 20);
+    assert_eq!(client.total_supply_at(&before_split), 30);
+    assert_eq!(client.balance_at(&holder1, &after_split), 20);
+    assert_eq!(client.balance_at(&holder2, &after_split), 40);
+    assert_eq!(client.total_supply_at(&after_split), 60);
+    assert_eq!(client.balance(&holder1), 21);
+}
+
+//// documents
+
+#[test]
+fn test_set_document_should_require_owner_auth_and_emit_a_document_updated_event() {
+    let e = setup_env();
+    let (owner, token_address, client) = deploy_token(&e);
+    let name: Symbol = Symbol::new(&e, "prospectus");
+    let uri: String = String::from_str(&e, "https://example.com/prospectus.pdf");
+    let hash: BytesN<32> = BytesN::from_array(&e, &[1; 32]);
+
+    client.set_document(&name, &uri, &hash, &owner);
+
+    let auths = e.auths();
+    assert_eq!(auths.len(), 1);
+    let (addr, _invocation) = &auths[0];
+    assert_eq!(addr, &owner);
+    let events = e.events().all();
+    let document_event = Vec::get(&events, 0).expect("Event should be present");
+    assert_eq!(document_event.0, token_address);
+    assert_eq!(
+        document_event.2.to_xdr(&e),
+        (name.clone(), uri.clone(), hash.clone()).to_xdr(&e)
+    );
+    let document = client.get_document(&name).unwrap();
+    assert_eq!(document.uri, uri);
+    assert_eq!(document.hash, hash);
+    assert_eq!(client.all_documents(), Vec::from_array(&e, [name]));
+}
+
+#[test]
+fn test_set_document_should_pass_for_a_doc_manager_and_fail_for_others() {
+    let e = setup_env();
+    let doc_manager: Address = Address::generate(&e);
+    let stranger: Address = Address::generate(&e);
+    let (_, _, client) = deploy_token(&e);
+    let (admin, permission_manager_address, permission_manager_client) =
+        deploy_permission_manager(&e);
+    client.set_permission_manager(&permission_manager_address);
+    permission_manager_client.grant_role(&admin, &doc_manager, &DOC_MANAGER_ROLE);
+    let name: Symbol = Symbol::new(&e, "kiid");
+    let uri: String = String::from_str(&e, "https://example.com/kiid.pdf");
+    let hash: BytesN<32> = BytesN::from_array(&e, &[2; 32]);
+
+    let result = client.try_set_document(&name, &uri, &hash, &stranger);
+    assert!(result.is_err());
+
+    client.set_document(&name, &uri, &hash, &doc_manager);
+    assert_eq!(client.get_document(&name).unwrap().uri, uri);
+}
+
+#[test]
+fn test_set_document_should_fail_if_there_are_too_many_documents() {
+    let e = setup_env();
+    let (owner, _, client) = deploy_token(&e);
+    let uri: String = String::from_str(&e, "https://example.com/document.pdf");
+    let hash: BytesN<32> = BytesN::from_array(&e, &[3; 32]);
+    for index in 0..20 {
+        let name = Symbol::new(&e, &std::format!("document_{index}"));
+        client.set_document(&name, &uri, &hash, &owner);
+    }
+    let first_document = Symbol::new(&e, "document_0");
+
+    let result = client.try_set_document(&Symbol::new(&e, "document_20"), &uri, &hash, &owner);
+    assert!(result.is_err());
+
+    client.set_document(&first_document, &uri, &hash, &owner);
+    client.remove_document(&first_document, &owner);
+    client.set_document(&Symbol::new(&e, "document_20"), &uri, &hash, &owner);
+    assert_eq!(client.all_documents().len(), 20);
+}
+
+#[test]
+fn test_remove_document_should_remove_it_from_the_registry() {
+    let e = setup_env();
+    let (owner, _, client) = deploy_token(&e);
+    let prospectus: Symbol = Symbol::new(&e, "prospectus");
+    let kiid: Symbol = Symbol::new(&e, "kiid");
+    let uri: String = String::from_str(&e, "https://example.com/document.pdf");
+    let hash: BytesN<32> = BytesN::from_array(&e, &[3; 32]);
+    client.set_document(&prospectus, &uri, &hash, &owner);
+    client.set_document(&kiid, &uri, &hash, &owner);
+
+    client.remove_document(&prospectus, &owner);
+
+    assert_eq!(client.all_documents(), Vec::from_array(&e, [kiid]));
+    assert_eq!(client.get_document(&prospectus), None);
+    let result = client.try_remove_document(&prospectus, &owner);
+    assert!(result.is_err());
+}
@@ -38,6 +38,8 @@ fi
 
 PERMISSION_MANAGER_ADDRESS=$(jq -r '.permissionManager' "$JSON_FILE")
 REDEMPTION_ADDRESS=$(jq -r '.redemption' "$JSON_FILE")
+DISTRIBUTION_ADDRESS=$(jq -r '.distribution // empty' "$JSON_FILE")
+TOKEN_ADDRESSES=$(jq -r '.tokens[]' "$JSON_FILE")
 EUTBL_ADDRESS=$(jq -r '.tokens.EUTBL' "$JSON_FILE")
 USTBL_ADDRESS=$(jq -r '.tokens.USTBL' "$JSON_FILE")
 EUR_USTBL_ADDRESS=$(jq -r '.tokens.EUR_USTBL' "$JSON_FILE")
@@ -339,6 +341,36 @@ give_burner_role_to_redemption() {
     { set +x; } 2>/dev/null
 }
 
+give_minter_role_on_token_to_redemption() {
+    set -x
+    stellar contract invoke \
+      --id $PERMISSION_MANAGER_ADDRESS \
+      --source $STELLAR_PROFILE \
+      --network $NETWORK \
+      -- \
+      grant_scoped_role \
+      --caller $STELLAR_PROFILE \
+      --account $REDEMPTION_ADDRESS \
+      --role MINTER \
+      --scope $1
+    { set +x; } 2>/dev/null
+}
+
+give_minter_role_on_token_to_distribution() {
+    set -x
+    stellar contract invoke \
+      --id $PERMISSION_MANAGER_ADDRESS \
+      --source $STELLAR_PROFILE \
+      --network $NETWORK \
+      -- \
+      grant_scoped_role \
+      --caller $STELLAR_PROFILE \
+      --account $DISTRIBUTION_ADDRESS \
+      --role MINTER \
+      --scope $1
+    { set +x; } 2>/dev/null
+}
+
 give_whitelister_role_to_relayer() {
     set -x
     stellar contract invoke \
@@ -386,6 +418,7 @@ echo "🌍 Stellar Profile: $STELLAR_PROFILE"
 echo "📋 Loaded Addresses:"
 echo "  Permission Manager: $PERMISSION_MANAGER_ADDRESS"
 echo "  Redemption: $REDEMPTION_ADDRESS"
+echo "  Distribution: $DISTRIBUTION_ADDRESS"
 echo "  EUTBL: $EUTBL_ADDRESS"
 echo "  USTBL: $USTBL_ADDRESS"
 echo "  eurUSTBL: $EUR_USTBL_ADDRESS"
@@ -465,9 +498,21 @@ echo "---> Give WHITELISTED_ROLE to redemption"
 give_whitelisted_role_to_redemption
 echo "---> Give REDEMPTION_EXECUTOR_ROLE to redemption"
 give_burner_role_to_redemption
+echo "---> Give MINTER_ROLE to redemption on each share class it switches to"
+for TOKEN_ADDRESS in $USTBL_ADDRESS $EUR_USTBL_ADDRESS $UKTBL_ADDRESS $EUR_UKTBL_ADDRESS $SPKCC_ADDRESS $EUR_SPKCC_ADDRESS; do
+    if [ "$TOKEN_ADDRESS" != "null" ]; then
+        give_minter_role_on_token_to_redemption $TOKEN_ADDRESS
+    fi
+done
 echo "---> Give WHITELISTER_ROLE to relayer"
 give_whitelister_role_to_relayer
 echo "---> Give MINTER_ROLE to relayer"
 give_minter_role_to_relayer
 echo "---> Give REDEMPTION_EXECUTOR_ROLE to relayer"
 give_redemption_executor_role_to_relayer
+if [ -n "$DISTRIBUTION_ADDRESS" ]; then
+    echo "---> Give MINTER_ROLE to distribution on each token (reinvestment)"
+    for TOKEN_ADDRESS in $TOKEN_ADDRESSES; do
+        give_minter_role_on_token_to_distribution $TOKEN_ADDRESS
+    done
+fi